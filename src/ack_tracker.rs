@@ -0,0 +1,131 @@
+use std::mem;
+
+use scheduler_client::SchedulerClient;
+use proto::mesos::{AgentID, TaskID, TaskStatus};
+
+// An update that still needs to be acknowledged to the master.
+#[derive(Clone, Debug)]
+pub struct PendingAck {
+    pub agent_id: AgentID,
+    pub task_id: TaskID,
+    pub uuid: Vec<u8>,
+    pub attempts: u32,
+}
+
+// Tracks status updates for frameworks that set
+// SchedulerConf::implicit_acknowledgements to false.
+//
+// Record every update as it arrives, then call acknowledge once the
+// update has been durably processed.  Acknowledgements are sent in
+// batches of batch_size, and ones that fail are retried on the next
+// flush until max_attempts is reached.
+pub struct AckTracker {
+    pub batch_size: usize,
+    pub max_attempts: u32,
+    pending: Vec<PendingAck>,
+    ready: Vec<PendingAck>,
+    failed: Vec<PendingAck>,
+}
+
+impl AckTracker {
+    pub fn new() -> AckTracker {
+        AckTracker {
+            batch_size: 1,
+            max_attempts: 5,
+            pending: vec![],
+            ready: vec![],
+            failed: vec![],
+        }
+    }
+
+    // Returns false for updates that carry no uuid, such as
+    // reconciliation responses, since the master rejects
+    // acknowledgements for them.
+    pub fn record(&mut self, status: &TaskStatus) -> bool {
+        if !status.has_uuid() {
+            return false;
+        }
+        let uuid = status.get_uuid();
+        let known = self.pending
+                        .iter()
+                        .chain(self.ready.iter())
+                        .any(|a| a.uuid == uuid);
+        if known {
+            return true;
+        }
+        self.pending.push(PendingAck {
+            agent_id: status.get_agent_id().clone(),
+            task_id: status.get_task_id().clone(),
+            uuid: uuid.to_vec(),
+            attempts: 0,
+        });
+        true
+    }
+
+    // Marks a recorded update as processed, so it is sent on the next
+    // flush.  Returns false if the update was never recorded.
+    pub fn processed(&mut self, status: &TaskStatus) -> bool {
+        let uuid = status.get_uuid();
+        match self.pending.iter().position(|a| a.uuid == uuid) {
+            Some(idx) => {
+                let ack = self.pending.remove(idx);
+                self.ready.push(ack);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Marks an update as processed and flushes once a full batch is
+    // ready.
+    pub fn acknowledge(&mut self,
+                       client: &SchedulerClient,
+                       status: &TaskStatus)
+                       -> bool {
+        let known = self.processed(status);
+        if self.ready.len() >= self.batch_size {
+            self.flush(client);
+        }
+        known
+    }
+
+    // Sends every ready acknowledgement, returning how many the master
+    // accepted.
+    pub fn flush(&mut self, client: &SchedulerClient) -> usize {
+        let mut sent = 0;
+        let ready = mem::replace(&mut self.ready, vec![]);
+        for mut ack in ready {
+            ack.attempts += 1;
+            let ok = match client.acknowledge(ack.agent_id.clone(),
+                                              ack.task_id.clone(),
+                                              ack.uuid.clone()) {
+                Ok(res) => res.status.is_success(),
+                Err(_) => false,
+            };
+            if ok {
+                sent += 1;
+            } else if ack.attempts >= self.max_attempts {
+                self.failed.push(ack);
+            } else {
+                self.ready.push(ack);
+            }
+        }
+        sent
+    }
+
+    // Updates recorded but not yet processed.
+    pub fn pending(&self) -> &[PendingAck] {
+        &self.pending
+    }
+
+    // Updates processed but not yet acknowledged, including ones
+    // waiting to be retried.
+    pub fn ready(&self) -> &[PendingAck] {
+        &self.ready
+    }
+
+    // Acknowledgements that gave up after max_attempts.
+    pub fn take_failed(&mut self) -> Vec<PendingAck> {
+        self.failed.drain(..).collect()
+    }
+}
@@ -0,0 +1,308 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use revive_manager::ReviveManager;
+use scheduler::Scheduler;
+use scheduler_client::SchedulerClient;
+use task_queue::{PackingStrategy, TaskQueue};
+use task_registry;
+use proto::*;
+use util;
+
+// One launch of a job, as reported by its status updates.
+#[derive(Clone, Debug)]
+pub struct Attempt {
+    pub task_id: TaskID,
+    pub agent_id: Option<AgentID>,
+    pub state: TaskState,
+    pub reason: Option<TaskStatus_Reason>,
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobState {
+    // Waiting for resources, for its first attempt or a retry.
+    Queued,
+    Running,
+    Succeeded,
+    // Every attempt failed.
+    Failed,
+}
+
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub name: String,
+    pub state: JobState,
+    pub attempts: Vec<Attempt>,
+    template: TaskInfo,
+}
+
+impl Job {
+    pub fn is_done(&self) -> bool {
+        self.state == JobState::Succeeded || self.state == JobState::Failed
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchSummary {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub attempts: usize,
+}
+
+// Submits jobs to a running BatchScheduler from another thread.  They
+// are picked up on the scheduler's next event.
+#[derive(Clone)]
+pub struct BatchHandle {
+    submissions: Arc<Mutex<Vec<(String, TaskInfo)>>>,
+}
+
+impl BatchHandle {
+    pub fn submit(&self, name: &str, task: TaskInfo) {
+        self.submissions.lock().unwrap().push((name.to_string(), task));
+    }
+}
+
+// A Scheduler that runs submitted jobs to completion.
+//
+// Jobs are TaskInfo templates, as for TaskQueue, with the task id set
+// to "<job>.<attempt>" on each launch.  A job succeeds when a task
+// finishes, and is retried until max_attempts tasks have failed, been
+// killed or been lost.  Once every job is done the completion callback
+// is called, and the framework is torn down if teardown_on_completion
+// is set.  Submitting more jobs afterwards starts a new batch.
+//
+// Status updates are expected to be acknowledged implicitly.
+pub struct BatchScheduler {
+    pub max_attempts: u32,
+    pub teardown_on_completion: bool,
+    queue: TaskQueue,
+    jobs: Vec<Job>,
+    handle: BatchHandle,
+    revive: ReviveManager,
+    completed: bool,
+    on_complete: Option<Box<FnMut(&BatchSummary, &[Job])>>,
+}
+
+impl BatchScheduler {
+    pub fn new(strategy: PackingStrategy) -> BatchScheduler {
+        BatchScheduler {
+            max_attempts: 3,
+            teardown_on_completion: false,
+            queue: TaskQueue::new(strategy),
+            jobs: vec![],
+            handle: BatchHandle { submissions: Arc::new(Mutex::new(vec![])) },
+            revive: ReviveManager::new(),
+            completed: false,
+            on_complete: None,
+        }
+    }
+
+    pub fn on_complete<F>(&mut self, callback: F)
+        where F: FnMut(&BatchSummary, &[Job]) + 'static
+    {
+        self.on_complete = Some(Box::new(callback));
+    }
+
+    pub fn handle(&self) -> BatchHandle {
+        self.handle.clone()
+    }
+
+    // Job names must be unique within the scheduler.
+    pub fn submit(&mut self, name: &str, task: TaskInfo) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("jobs require a name".to_string());
+        }
+        if self.job(name).is_some() {
+            return Err(format!("job {} was already submitted", name));
+        }
+        let mut job = Job {
+            name: name.to_string(),
+            state: JobState::Queued,
+            attempts: vec![],
+            template: task,
+        };
+        enqueue(&mut self.queue, &mut job);
+        self.jobs.push(job);
+        self.completed = false;
+        Ok(())
+    }
+
+    pub fn job(&self, name: &str) -> Option<&Job> {
+        self.jobs.iter().find(|j| j.name == name)
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed
+    }
+
+    pub fn summary(&self) -> BatchSummary {
+        BatchSummary {
+            succeeded: self.jobs
+                           .iter()
+                           .filter(|j| j.state == JobState::Succeeded)
+                           .count(),
+            failed: self.jobs
+                        .iter()
+                        .filter(|j| j.state == JobState::Failed)
+                        .count(),
+            attempts: self.jobs.iter().fold(0, |acc, j| acc + j.attempts.len()),
+        }
+    }
+
+    fn accept_submissions(&mut self) {
+        let submissions = mem::replace(&mut *self.handle
+                                                 .submissions
+                                                 .lock()
+                                                 .unwrap(),
+                                       vec![]);
+        for (name, task) in submissions {
+            // Duplicates have nowhere to be reported from here.
+            let _ = self.submit(&name, task);
+        }
+    }
+
+    fn finish(&mut self, client: &SchedulerClient) {
+        if self.completed || self.jobs.is_empty() ||
+           !self.jobs.iter().all(|j| j.is_done()) {
+            return;
+        }
+        self.completed = true;
+        let summary = self.summary();
+        if let Some(ref mut callback) = self.on_complete {
+            callback(&summary, &self.jobs);
+        }
+        if self.teardown_on_completion {
+            client.teardown();
+        }
+    }
+}
+
+impl Scheduler for BatchScheduler {
+    fn subscribed(&mut self,
+                  client: &SchedulerClient,
+                  _: &FrameworkID,
+                  _: Option<f64>) {
+        self.accept_submissions();
+        let pending = self.queue.len();
+        self.revive.set_pending(client, pending);
+        self.revive.subscribed(client);
+    }
+
+    fn offers(&mut self, client: &SchedulerClient, offers: Vec<&Offer>) {
+        self.accept_submissions();
+        // Only tasks the master accepted come back; the rest stay queued.
+        for task in self.queue.offers(client, offers) {
+            let task_id = task.get_task_id();
+            let job = match self.jobs.iter_mut().find(|j| {
+                j.state == JobState::Queued &&
+                next_task_id(j).get_value() == task_id.get_value()
+            }) {
+                Some(job) => job,
+                None => continue,
+            };
+            job.state = JobState::Running;
+            job.attempts.push(Attempt {
+                task_id: task_id.clone(),
+                agent_id: Some(task.get_agent_id().clone()),
+                state: TaskState::TASK_STAGING,
+                reason: None,
+                message: None,
+            });
+        }
+        let pending = self.queue.len();
+        self.revive.set_pending(client, pending);
+    }
+
+    fn inverse_offers(&mut self,
+                      _: &SchedulerClient,
+                      _: Vec<&InverseOffer>) {
+    }
+
+    fn rescind(&mut self, _: &SchedulerClient, _: &OfferID) {}
+
+    fn update(&mut self, client: &SchedulerClient, status: &TaskStatus) {
+        self.accept_submissions();
+        let idx = self.jobs.iter().position(|j| {
+            j.state == JobState::Running &&
+            j.attempts.last().map_or(false, |a| {
+                a.task_id.get_value() == status.get_task_id().get_value()
+            })
+        });
+        if let Some(idx) = idx {
+            let job = &mut self.jobs[idx];
+            {
+                let attempt = job.attempts.last_mut().unwrap();
+                attempt.state = status.get_state();
+                if status.has_agent_id() {
+                    attempt.agent_id = Some(status.get_agent_id().clone());
+                }
+                if status.has_reason() {
+                    attempt.reason = Some(status.get_reason());
+                }
+                if status.has_message() {
+                    attempt.message = Some(status.get_message().to_string());
+                }
+            }
+
+            let state = status.get_state();
+            if state == TaskState::TASK_FINISHED {
+                job.state = JobState::Succeeded;
+            } else if task_registry::is_terminal(&state) {
+                if (job.attempts.len() as u32) < self.max_attempts {
+                    enqueue(&mut self.queue, job);
+                } else {
+                    job.state = JobState::Failed;
+                }
+            }
+        }
+
+        self.finish(client);
+        let pending = self.queue.len();
+        self.revive.set_pending(client, pending);
+    }
+
+    fn message(&mut self,
+               _: &SchedulerClient,
+               _: &AgentID,
+               _: &ExecutorID,
+               _: Vec<u8>) {
+    }
+
+    fn failure(&mut self,
+               _: &SchedulerClient,
+               _: Option<&AgentID>,
+               _: Option<&ExecutorID>,
+               _: Option<i32>) {
+    }
+
+    fn error(&mut self, _: &SchedulerClient, _: String) {}
+
+    fn heartbeat(&mut self, client: &SchedulerClient) {
+        self.accept_submissions();
+        let pending = self.queue.len();
+        self.revive.set_pending(client, pending);
+    }
+
+    fn disconnected(&mut self) {
+        self.revive.disconnected();
+    }
+}
+
+fn next_task_id(job: &Job) -> TaskID {
+    util::task_id(&format!("{}.{}", job.name, job.attempts.len()))
+}
+
+fn enqueue(queue: &mut TaskQueue, job: &mut Job) {
+    let mut task = job.template.clone();
+    if task.get_name().is_empty() {
+        task.set_name(job.name.clone());
+    }
+    task.set_task_id(next_task_id(job));
+    job.state = JobState::Queued;
+    queue.enqueue(task);
+}
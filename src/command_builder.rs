@@ -0,0 +1,131 @@
+use protobuf;
+
+use proto::mesos::{CommandInfo, CommandInfo_URI, Environment,
+                   Environment_Variable};
+
+// Builds a CommandInfo_URI for the fetcher.
+#[derive(Clone)]
+pub struct UriBuilder {
+    uri: CommandInfo_URI,
+}
+
+impl UriBuilder {
+    pub fn new(value: &str) -> UriBuilder {
+        let mut uri = CommandInfo_URI::new();
+        uri.set_value(value.to_string());
+        UriBuilder { uri: uri }
+    }
+
+    pub fn extract(mut self, extract: bool) -> UriBuilder {
+        self.uri.set_extract(extract);
+        self
+    }
+
+    pub fn executable(mut self, executable: bool) -> UriBuilder {
+        self.uri.set_executable(executable);
+        self
+    }
+
+    pub fn cache(mut self, cache: bool) -> UriBuilder {
+        self.uri.set_cache(cache);
+        self
+    }
+
+    pub fn build(self) -> CommandInfo_URI {
+        self.uri
+    }
+}
+
+// Builds a CommandInfo, either run through `sh -c` or exec'd directly
+// with an argv.
+#[derive(Clone)]
+pub struct CommandBuilder {
+    shell: bool,
+    value: Option<String>,
+    arguments: Vec<String>,
+    uris: Vec<CommandInfo_URI>,
+    environment: Vec<Environment_Variable>,
+    user: Option<String>,
+}
+
+impl CommandBuilder {
+    pub fn shell(value: &str) -> CommandBuilder {
+        CommandBuilder {
+            shell: true,
+            value: Some(value.to_string()),
+            arguments: vec![],
+            uris: vec![],
+            environment: vec![],
+            user: None,
+        }
+    }
+
+    // argv[0] is the executable to run, and is also passed as the first
+    // argument, as with execv.
+    pub fn argv(argv: Vec<&str>) -> CommandBuilder {
+        CommandBuilder {
+            shell: false,
+            value: argv.first().map(|v| v.to_string()),
+            arguments: argv.iter().map(|a| a.to_string()).collect(),
+            uris: vec![],
+            environment: vec![],
+            user: None,
+        }
+    }
+
+    pub fn uri(mut self, uri: CommandInfo_URI) -> CommandBuilder {
+        self.uris.push(uri);
+        self
+    }
+
+    // Fetches a plain URI with the fetcher's defaults.
+    pub fn fetch(self, value: &str) -> CommandBuilder {
+        self.uri(UriBuilder::new(value).build())
+    }
+
+    pub fn env(mut self, name: &str, value: &str) -> CommandBuilder {
+        let mut variable = Environment_Variable::new();
+        variable.set_name(name.to_string());
+        variable.set_value(value.to_string());
+        self.environment.push(variable);
+        self
+    }
+
+    // Runs the command as this user instead of the framework's user.
+    pub fn user(mut self, user: &str) -> CommandBuilder {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    pub fn build(self) -> Result<CommandInfo, String> {
+        let value = self.value.unwrap_or(String::new());
+        if self.shell && value.trim().is_empty() {
+            return Err("shell commands require a value".to_string());
+        }
+        if !self.shell && (self.arguments.is_empty() || value.is_empty()) {
+            return Err("argv commands require arguments".to_string());
+        }
+        if self.uris.iter().any(|u| u.get_value().is_empty()) {
+            return Err("uris require a value".to_string());
+        }
+
+        let mut command = CommandInfo::new();
+        command.set_shell(self.shell);
+        command.set_value(value);
+        if !self.shell {
+            command.set_arguments(
+                protobuf::RepeatedField::from_vec(self.arguments));
+        }
+        command.set_uris(protobuf::RepeatedField::from_vec(self.uris));
+        if self.environment.len() > 0 {
+            let mut environment = Environment::new();
+            environment.set_variables(
+                protobuf::RepeatedField::from_vec(self.environment));
+            command.set_environment(environment);
+        }
+        if let Some(user) = self.user {
+            command.set_user(user);
+        }
+        Ok(command)
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use proto::mesos::{Attribute, Offer, TaskID, Value_Type};
+
+#[derive(Clone, Debug)]
+pub enum Operator {
+    // At most one task per value of the field.
+    Unique,
+    // Only place tasks where the field has this value.
+    Cluster(String),
+    // Spread tasks evenly across values of the field.  With a group
+    // count, values not seen yet are counted as empty groups.
+    GroupBy(Option<usize>),
+    // The field must fully match the pattern.
+    Like(Regex),
+    // The field must not fully match the pattern.
+    Unlike(Regex),
+    // At most this many tasks per value of the field.
+    MaxPer(usize),
+}
+
+// A placement rule over an agent attribute, or "hostname", in the style
+// of Marathon constraints.
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    pub field: String,
+    pub operator: Operator,
+}
+
+// Where one of our tasks is running, captured from the offer it was
+// launched on.
+#[derive(Clone, Debug)]
+pub struct PlacedTask {
+    pub task_id: TaskID,
+    pub hostname: String,
+    pub attributes: HashMap<String, String>,
+}
+
+impl PlacedTask {
+    pub fn from_offer(task_id: &TaskID, offer: &Offer) -> PlacedTask {
+        PlacedTask {
+            task_id: task_id.clone(),
+            hostname: offer.get_hostname().to_string(),
+            attributes: offer.get_attributes()
+                             .iter()
+                             .map(|a| {
+                                 (a.get_name().to_string(),
+                                  attribute_value(a))
+                             })
+                             .collect(),
+        }
+    }
+
+    fn field(&self, field: &str) -> Option<&str> {
+        if field == "hostname" {
+            Some(&self.hostname)
+        } else {
+            self.attributes.get(field).map(|v| &**v)
+        }
+    }
+}
+
+impl Constraint {
+    pub fn unique(field: &str) -> Constraint {
+        Constraint::new(field, Operator::Unique)
+    }
+
+    pub fn cluster(field: &str, value: &str) -> Constraint {
+        Constraint::new(field, Operator::Cluster(value.to_string()))
+    }
+
+    pub fn group_by(field: &str, groups: Option<usize>) -> Constraint {
+        Constraint::new(field, Operator::GroupBy(groups))
+    }
+
+    pub fn like(field: &str, pattern: &str) -> Result<Constraint, String> {
+        let regex = try!(anchored(pattern));
+        Ok(Constraint::new(field, Operator::Like(regex)))
+    }
+
+    pub fn unlike(field: &str, pattern: &str) -> Result<Constraint, String> {
+        let regex = try!(anchored(pattern));
+        Ok(Constraint::new(field, Operator::Unlike(regex)))
+    }
+
+    pub fn max_per(field: &str, max: usize) -> Constraint {
+        Constraint::new(field, Operator::MaxPer(max))
+    }
+
+    fn new(field: &str, operator: Operator) -> Constraint {
+        Constraint {
+            field: field.to_string(),
+            operator: operator,
+        }
+    }
+
+    // Parses Marathon's "field:OPERATOR[:value]" syntax, e.g.
+    // "hostname:UNIQUE" or "rack:GROUP_BY:3".
+    pub fn parse(constraint: &str) -> Result<Constraint, String> {
+        let parts: Vec<&str> = constraint.splitn(3, ':').collect();
+        if parts.len() < 2 || parts[0].is_empty() {
+            return Err(format!("invalid constraint {}", constraint));
+        }
+        let field = parts[0];
+        let value = parts.get(2).map(|v| *v);
+        match (parts[1], value) {
+            ("UNIQUE", None) => Ok(Constraint::unique(field)),
+            ("CLUSTER", Some(value)) => Ok(Constraint::cluster(field, value)),
+            ("GROUP_BY", None) => Ok(Constraint::group_by(field, None)),
+            ("GROUP_BY", Some(groups)) => {
+                let groups = try!(groups.parse::<usize>().map_err(|e| {
+                    format!("invalid group count {}: {}", groups, e)
+                }));
+                Ok(Constraint::group_by(field, Some(groups)))
+            }
+            ("LIKE", Some(pattern)) => Constraint::like(field, pattern),
+            ("UNLIKE", Some(pattern)) => Constraint::unlike(field, pattern),
+            ("MAX_PER", Some(max)) => {
+                let max = try!(max.parse::<usize>().map_err(|e| {
+                    format!("invalid maximum {}: {}", max, e)
+                }));
+                Ok(Constraint::max_per(field, max))
+            }
+            _ => Err(format!("invalid constraint {}", constraint)),
+        }
+    }
+
+    // Whether a new task may be placed on the offer's agent, given where
+    // the tasks it is grouped with already run.  Offers missing the
+    // field only satisfy UNLIKE.
+    pub fn satisfied(&self, offer: &Offer, running: &[&PlacedTask]) -> bool {
+        let value = match offer_field(offer, &self.field) {
+            Some(value) => value,
+            None => {
+                return match self.operator {
+                    Operator::Unlike(_) => true,
+                    _ => false,
+                }
+            }
+        };
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for task in running {
+            if let Some(v) = task.field(&self.field) {
+                *counts.entry(v).or_insert(0) += 1;
+            }
+        }
+        let count = counts.get(&*value).cloned().unwrap_or(0);
+
+        match self.operator {
+            Operator::Unique => count == 0,
+            Operator::Cluster(ref expected) => value == *expected,
+            Operator::GroupBy(groups) => {
+                let unseen_groups = groups.map_or(false, |n| counts.len() < n);
+                let min = if unseen_groups {
+                    0
+                } else {
+                    counts.values().cloned().min().unwrap_or(0)
+                };
+                count <= min
+            }
+            Operator::Like(ref regex) => regex.is_match(&value),
+            Operator::Unlike(ref regex) => !regex.is_match(&value),
+            Operator::MaxPer(max) => count < max,
+        }
+    }
+}
+
+pub fn satisfies_all(constraints: &[Constraint],
+                     offer: &Offer,
+                     running: &[&PlacedTask])
+                     -> bool {
+    constraints.iter().all(|c| c.satisfied(offer, running))
+}
+
+fn anchored(pattern: &str) -> Result<Regex, String> {
+    Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| e.to_string())
+}
+
+fn offer_field(offer: &Offer, field: &str) -> Option<String> {
+    if field == "hostname" {
+        return Some(offer.get_hostname().to_string());
+    }
+    offer.get_attributes()
+         .iter()
+         .find(|a| a.get_name() == field)
+         .map(attribute_value)
+}
+
+// Renders an attribute the way Mesos prints it, so text, scalar, range
+// and set attributes can all be compared as strings.
+fn attribute_value(attribute: &Attribute) -> String {
+    match attribute.get_field_type() {
+        Value_Type::TEXT => attribute.get_text().get_value().to_string(),
+        Value_Type::SCALAR => format!("{}", attribute.get_scalar().get_value()),
+        Value_Type::RANGES => {
+            let ranges: Vec<String> = attribute.get_ranges()
+                                               .get_range()
+                                               .iter()
+                                               .map(|r| {
+                                                   format!("{}-{}",
+                                                           r.get_begin(),
+                                                           r.get_end())
+                                               })
+                                               .collect();
+            format!("[{}]", ranges.join(", "))
+        }
+        Value_Type::SET => {
+            format!("{{{}}}", attribute.get_set().get_item().join(","))
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use protobuf;
+
+use proto::mesos::{ContainerInfo, ContainerInfo_DockerInfo,
+                   ContainerInfo_DockerInfo_Network,
+                   ContainerInfo_DockerInfo_PortMapping,
+                   ContainerInfo_MesosInfo, ContainerInfo_Type, Image,
+                   Image_AppC, Image_Type, Parameter, Volume, Volume_Mode};
+use util;
+
+pub fn host_volume(host_path: &str,
+                   container_path: &str,
+                   mode: Volume_Mode)
+                   -> Volume {
+    let mut volume = Volume::new();
+    volume.set_host_path(host_path.to_string());
+    volume.set_container_path(container_path.to_string());
+    volume.set_mode(mode);
+    volume
+}
+
+pub fn image_volume(image: Image,
+                    container_path: &str,
+                    mode: Volume_Mode)
+                    -> Volume {
+    let mut volume = Volume::new();
+    volume.set_image(image);
+    volume.set_container_path(container_path.to_string());
+    volume.set_mode(mode);
+    volume
+}
+
+// AppC is the only image type the Mesos containerizer supports in this
+// API version; Docker images go through DockerContainerBuilder.
+pub fn appc_image(name: &str,
+                  id: Option<&str>,
+                  labels: Vec<(&str, &str)>)
+                  -> Image {
+    let mut appc = Image_AppC::new();
+    appc.set_name(name.to_string());
+    if let Some(id) = id {
+        appc.set_id(id.to_string());
+    }
+    if labels.len() > 0 {
+        appc.set_labels(util::labels(labels));
+    }
+
+    let mut image = Image::new();
+    image.set_field_type(Image_Type::APPC);
+    image.set_appc(appc);
+    image
+}
+
+fn validate_volumes(volumes: &[Volume]) -> Result<(), String> {
+    for volume in volumes {
+        if volume.get_container_path().is_empty() {
+            return Err("volumes require a container path".to_string());
+        }
+        if volume.has_host_path() && volume.has_image() {
+            return Err(format!("volume {} can't have both a host path and \
+                                an image",
+                               volume.get_container_path()));
+        }
+    }
+    Ok(())
+}
+
+// Builds a ContainerInfo for the Docker containerizer.
+#[derive(Clone)]
+pub struct DockerContainerBuilder {
+    image: String,
+    network: Option<ContainerInfo_DockerInfo_Network>,
+    port_mappings: Vec<ContainerInfo_DockerInfo_PortMapping>,
+    privileged: Option<bool>,
+    parameters: Vec<Parameter>,
+    force_pull_image: Option<bool>,
+    volumes: Vec<Volume>,
+    hostname: Option<String>,
+}
+
+impl DockerContainerBuilder {
+    pub fn new(image: &str) -> DockerContainerBuilder {
+        DockerContainerBuilder {
+            image: image.to_string(),
+            network: None,
+            port_mappings: vec![],
+            privileged: None,
+            parameters: vec![],
+            force_pull_image: None,
+            volumes: vec![],
+            hostname: None,
+        }
+    }
+
+    pub fn network(mut self,
+                   network: ContainerInfo_DockerInfo_Network)
+                   -> DockerContainerBuilder {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn port_mapping(mut self,
+                        host_port: u32,
+                        container_port: u32,
+                        protocol: &str)
+                        -> DockerContainerBuilder {
+        let mut mapping = ContainerInfo_DockerInfo_PortMapping::new();
+        mapping.set_host_port(host_port);
+        mapping.set_container_port(container_port);
+        mapping.set_protocol(protocol.to_string());
+        self.port_mappings.push(mapping);
+        self
+    }
+
+    pub fn privileged(mut self, privileged: bool) -> DockerContainerBuilder {
+        self.privileged = Some(privileged);
+        self
+    }
+
+    // Passed to `docker run` as --key=value.
+    pub fn parameter(mut self,
+                     key: &str,
+                     value: &str)
+                     -> DockerContainerBuilder {
+        let mut parameter = Parameter::new();
+        parameter.set_key(key.to_string());
+        parameter.set_value(value.to_string());
+        self.parameters.push(parameter);
+        self
+    }
+
+    pub fn force_pull_image(mut self,
+                            force_pull_image: bool)
+                            -> DockerContainerBuilder {
+        self.force_pull_image = Some(force_pull_image);
+        self
+    }
+
+    pub fn volume(mut self, volume: Volume) -> DockerContainerBuilder {
+        self.volumes.push(volume);
+        self
+    }
+
+    pub fn host_volume(self,
+                       host_path: &str,
+                       container_path: &str,
+                       mode: Volume_Mode)
+                       -> DockerContainerBuilder {
+        self.volume(host_volume(host_path, container_path, mode))
+    }
+
+    pub fn hostname(mut self, hostname: &str) -> DockerContainerBuilder {
+        self.hostname = Some(hostname.to_string());
+        self
+    }
+
+    pub fn build(self) -> Result<ContainerInfo, String> {
+        if self.image.is_empty() {
+            return Err("docker containers require an image".to_string());
+        }
+        if self.port_mappings.len() > 0 &&
+           self.network != Some(ContainerInfo_DockerInfo_Network::BRIDGE) {
+            return Err("port mappings require BRIDGE networking"
+                           .to_string());
+        }
+        if self.volumes.iter().any(|v| v.has_image()) {
+            return Err("image volumes are not supported by docker"
+                           .to_string());
+        }
+        try!(validate_volumes(&self.volumes));
+
+        let mut docker = ContainerInfo_DockerInfo::new();
+        docker.set_image(self.image);
+        if let Some(network) = self.network {
+            docker.set_network(network);
+        }
+        docker.set_port_mappings(
+            protobuf::RepeatedField::from_vec(self.port_mappings));
+        if let Some(privileged) = self.privileged {
+            docker.set_privileged(privileged);
+        }
+        docker.set_parameters(
+            protobuf::RepeatedField::from_vec(self.parameters));
+        if let Some(force_pull_image) = self.force_pull_image {
+            docker.set_force_pull_image(force_pull_image);
+        }
+
+        let mut container = ContainerInfo::new();
+        container.set_field_type(ContainerInfo_Type::DOCKER);
+        container.set_docker(docker);
+        container.set_volumes(protobuf::RepeatedField::from_vec(self.volumes));
+        if let Some(hostname) = self.hostname {
+            container.set_hostname(hostname);
+        }
+        Ok(container)
+    }
+}
+
+// Builds a ContainerInfo for the Mesos containerizer, optionally running
+// an image.
+#[derive(Clone)]
+pub struct MesosContainerBuilder {
+    image: Option<Image>,
+    volumes: Vec<Volume>,
+    hostname: Option<String>,
+}
+
+impl MesosContainerBuilder {
+    pub fn new() -> MesosContainerBuilder {
+        MesosContainerBuilder {
+            image: None,
+            volumes: vec![],
+            hostname: None,
+        }
+    }
+
+    pub fn image(mut self, image: Image) -> MesosContainerBuilder {
+        self.image = Some(image);
+        self
+    }
+
+    pub fn appc_image(self,
+                      name: &str,
+                      id: Option<&str>,
+                      labels: Vec<(&str, &str)>)
+                      -> MesosContainerBuilder {
+        self.image(appc_image(name, id, labels))
+    }
+
+    pub fn volume(mut self, volume: Volume) -> MesosContainerBuilder {
+        self.volumes.push(volume);
+        self
+    }
+
+    pub fn host_volume(self,
+                       host_path: &str,
+                       container_path: &str,
+                       mode: Volume_Mode)
+                       -> MesosContainerBuilder {
+        self.volume(host_volume(host_path, container_path, mode))
+    }
+
+    pub fn image_volume(self,
+                        image: Image,
+                        container_path: &str,
+                        mode: Volume_Mode)
+                        -> MesosContainerBuilder {
+        self.volume(image_volume(image, container_path, mode))
+    }
+
+    pub fn hostname(mut self, hostname: &str) -> MesosContainerBuilder {
+        self.hostname = Some(hostname.to_string());
+        self
+    }
+
+    pub fn build(self) -> Result<ContainerInfo, String> {
+        try!(validate_volumes(&self.volumes));
+
+        let mut container = ContainerInfo::new();
+        container.set_field_type(ContainerInfo_Type::MESOS);
+        if let Some(image) = self.image {
+            let mut mesos = ContainerInfo_MesosInfo::new();
+            mesos.set_image(image);
+            container.set_mesos(mesos);
+        }
+        container.set_volumes(protobuf::RepeatedField::from_vec(self.volumes));
+        if let Some(hostname) = self.hostname {
+            container.set_hostname(hostname);
+        }
+        Ok(container)
+    }
+}
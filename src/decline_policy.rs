@@ -0,0 +1,112 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use scheduler_client::SchedulerClient;
+use proto::mesos::{AgentID, Filters, Offer, OfferID};
+use util;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RefuseStrategy {
+    // Always refuse for this many seconds.
+    Fixed(f64),
+    // Refuse for initial seconds, doubling with each consecutive decline
+    // of the same agent up to max, until an offer from it is accepted.
+    Exponential { initial: f64, max: f64 },
+}
+
+// Computes the refuse_seconds to decline offers with.  Declining with
+// the default five seconds has the master offer the same resources back
+// over and over; backing off per agent, and refusing for much longer
+// while there is no work at all, cuts that churn down.
+pub struct DeclinePolicy {
+    pub strategy: RefuseStrategy,
+    // Used instead of the strategy while no work is pending.
+    pub idle_refuse_seconds: Option<f64>,
+    declines: HashMap<String, u32>,
+}
+
+impl DeclinePolicy {
+    pub fn new(strategy: RefuseStrategy) -> DeclinePolicy {
+        DeclinePolicy {
+            strategy: strategy,
+            idle_refuse_seconds: Some(300f64),
+            declines: HashMap::new(),
+        }
+    }
+
+    // The refuse_seconds for the next decline of the agent's offers,
+    // counting it as a decline.
+    pub fn refuse_seconds(&mut self,
+                          agent_id: &AgentID,
+                          work_pending: bool)
+                          -> f64 {
+        if !work_pending {
+            if let Some(seconds) = self.idle_refuse_seconds {
+                return seconds;
+            }
+        }
+
+        let declines = self.declines
+                           .entry(agent_id.get_value().to_string())
+                           .or_insert(0);
+        *declines += 1;
+        match self.strategy {
+            RefuseStrategy::Fixed(seconds) => seconds,
+            RefuseStrategy::Exponential { initial, max } => {
+                let exponent = cmp::min(*declines - 1, 31);
+                (initial * 2f64.powi(exponent as i32)).min(max)
+            }
+        }
+    }
+
+    pub fn filters(&mut self,
+                   agent_id: &AgentID,
+                   work_pending: bool)
+                   -> Filters {
+        util::filters(self.refuse_seconds(agent_id, work_pending))
+    }
+
+    // Resets the backoff of an agent whose offer was used.
+    pub fn accepted(&mut self, agent_id: &AgentID) {
+        self.declines.remove(agent_id.get_value());
+    }
+
+    // Resets every agent's backoff, e.g. when new work arrives that
+    // earlier declines did not account for.
+    pub fn reset(&mut self) {
+        self.declines.clear();
+    }
+
+    pub fn declines(&self, agent_id: &AgentID) -> u32 {
+        self.declines.get(agent_id.get_value()).cloned().unwrap_or(0)
+    }
+
+    // Declines the offers, with one DECLINE per distinct refuse_seconds.
+    pub fn decline(&mut self,
+                   client: &SchedulerClient,
+                   offers: Vec<&Offer>,
+                   work_pending: bool) {
+        let mut batches: Vec<(f64, Vec<OfferID>)> = vec![];
+        let mut agents: Vec<(&str, f64)> = vec![];
+        for offer in offers {
+            // Several offers from one agent count as a single decline.
+            let agent = offer.get_agent_id().get_value();
+            let seconds = match agents.iter().find(|&&(a, _)| a == agent) {
+                Some(&(_, seconds)) => seconds,
+                None => {
+                    let seconds = self.refuse_seconds(offer.get_agent_id(),
+                                                      work_pending);
+                    agents.push((agent, seconds));
+                    seconds
+                }
+            };
+            match batches.iter().position(|&(s, _)| s == seconds) {
+                Some(idx) => batches[idx].1.push(offer.get_id().clone()),
+                None => batches.push((seconds, vec![offer.get_id().clone()])),
+            }
+        }
+        for (seconds, offer_ids) in batches {
+            client.decline(offer_ids, Some(util::filters(seconds)));
+        }
+    }
+}
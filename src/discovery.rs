@@ -0,0 +1,141 @@
+use protobuf;
+
+use proto::mesos::{DiscoveryInfo, DiscoveryInfo_Visibility, Port, Ports};
+use util;
+
+// Builds the DiscoveryInfo that DNS-based discovery tools read from a
+// task.  Ports are best added with allocated_ports, straight from what
+// a PortAllocator handed out, so the advertised ports always match the
+// task's "ports" resource.
+#[derive(Clone)]
+pub struct DiscoveryInfoBuilder {
+    name: String,
+    visibility: DiscoveryInfo_Visibility,
+    environment: Option<String>,
+    location: Option<String>,
+    version: Option<String>,
+    ports: Vec<Port>,
+    labels: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+impl DiscoveryInfoBuilder {
+    pub fn new(name: &str,
+               visibility: DiscoveryInfo_Visibility)
+               -> DiscoveryInfoBuilder {
+        DiscoveryInfoBuilder {
+            name: name.to_string(),
+            visibility: visibility,
+            environment: None,
+            location: None,
+            version: None,
+            ports: vec![],
+            labels: vec![],
+            error: None,
+        }
+    }
+
+    pub fn environment(mut self, environment: &str) -> DiscoveryInfoBuilder {
+        self.environment = Some(environment.to_string());
+        self
+    }
+
+    pub fn location(mut self, location: &str) -> DiscoveryInfoBuilder {
+        self.location = Some(location.to_string());
+        self
+    }
+
+    pub fn version(mut self, version: &str) -> DiscoveryInfoBuilder {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    pub fn port(mut self,
+                number: u32,
+                name: &str,
+                protocol: &str)
+                -> DiscoveryInfoBuilder {
+        let mut port = Port::new();
+        port.set_number(number);
+        port.set_name(name.to_string());
+        port.set_protocol(protocol.to_string());
+        self.ports.push(port);
+        self
+    }
+
+    // Names allocated ports in order, with (name, protocol) pairs.  The
+    // number of names must match the number of ports.
+    pub fn allocated_ports(mut self,
+                           ports: &[u64],
+                           names: &[(&str, &str)])
+                           -> DiscoveryInfoBuilder {
+        if ports.len() != names.len() {
+            self.error = Some(format!("{} ports were allocated but {} were \
+                                       named",
+                                      ports.len(),
+                                      names.len()));
+            return self;
+        }
+        for (&number, &(name, protocol)) in ports.iter().zip(names.iter()) {
+            self = self.port(number as u32, name, protocol);
+        }
+        self
+    }
+
+    pub fn label(mut self, key: &str, value: &str) -> DiscoveryInfoBuilder {
+        self.labels.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn build(self) -> Result<DiscoveryInfo, String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.name.is_empty() {
+            return Err("discovery info requires a name".to_string());
+        }
+        for port in &self.ports {
+            if port.get_number() == 0 {
+                return Err(format!("port {} has no number", port.get_name()));
+            }
+            let duplicates = self.ports
+                                 .iter()
+                                 .filter(|p| {
+                                     p.get_name() == port.get_name() &&
+                                     p.get_protocol() == port.get_protocol()
+                                 })
+                                 .count();
+            if !port.get_name().is_empty() && duplicates > 1 {
+                return Err(format!("port name {} is used more than once",
+                                   port.get_name()));
+            }
+        }
+
+        let mut discovery = DiscoveryInfo::new();
+        discovery.set_name(self.name);
+        discovery.set_visibility(self.visibility);
+        if let Some(environment) = self.environment {
+            discovery.set_environment(environment);
+        }
+        if let Some(location) = self.location {
+            discovery.set_location(location);
+        }
+        if let Some(version) = self.version {
+            discovery.set_version(version);
+        }
+        if self.ports.len() > 0 {
+            let mut ports = Ports::new();
+            ports.set_ports(protobuf::RepeatedField::from_vec(self.ports));
+            discovery.set_ports(ports);
+        }
+        if self.labels.len() > 0 {
+            discovery.set_labels(util::labels(self.labels
+                                                  .iter()
+                                                  .map(|&(ref k, ref v)| {
+                                                      (&**k, &**v)
+                                                  })
+                                                  .collect()));
+        }
+        Ok(discovery)
+    }
+}
@@ -0,0 +1,133 @@
+use protobuf;
+
+use proto::mesos::{CommandInfo, ContainerInfo, DiscoveryInfo, ExecutorID,
+                   ExecutorInfo, FrameworkID, Offer, Resource};
+
+pub fn executor_id(id: &str) -> ExecutorID {
+    let mut executor_id = ExecutorID::new();
+    executor_id.set_value(id.to_string());
+    executor_id
+}
+
+// True if one of the offers reports the executor already running on its
+// agent.  A running executor is reused when more tasks are launched with
+// the same ExecutorInfo, and its resources are not needed again.
+pub fn is_running(offers: &[&Offer], executor_id: &ExecutorID) -> bool {
+    offers.iter()
+          .flat_map(|o| o.get_executor_ids())
+          .any(|e| e.get_value() == executor_id.get_value())
+}
+
+// Builds the ExecutorInfo for a custom executor.  Launch every task
+// meant for the same executor with an identical ExecutorInfo, as Mesos
+// rejects tasks whose ExecutorInfo differs from the running one.
+#[derive(Clone, Default)]
+pub struct ExecutorInfoBuilder {
+    executor_id: Option<ExecutorID>,
+    name: Option<String>,
+    command: Option<CommandInfo>,
+    container: Option<ContainerInfo>,
+    resources: Vec<Resource>,
+    framework_id: Option<FrameworkID>,
+    source: Option<String>,
+    data: Option<Vec<u8>>,
+    discovery: Option<DiscoveryInfo>,
+}
+
+impl ExecutorInfoBuilder {
+    pub fn new(id: &str) -> ExecutorInfoBuilder {
+        ExecutorInfoBuilder {
+            executor_id: Some(executor_id(id)),
+            ..ExecutorInfoBuilder::default()
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> ExecutorInfoBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn command(mut self, command: CommandInfo) -> ExecutorInfoBuilder {
+        self.command = Some(command);
+        self
+    }
+
+    pub fn container(mut self,
+                     container: ContainerInfo)
+                     -> ExecutorInfoBuilder {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn resource(mut self, resource: Resource) -> ExecutorInfoBuilder {
+        self.resources.push(resource);
+        self
+    }
+
+    pub fn resources(mut self,
+                     resources: Vec<Resource>)
+                     -> ExecutorInfoBuilder {
+        self.resources.extend(resources);
+        self
+    }
+
+    // Optional, SchedulerClient::launch_with_executor fills it in.
+    pub fn framework_id(mut self,
+                        framework_id: &FrameworkID)
+                        -> ExecutorInfoBuilder {
+        self.framework_id = Some(framework_id.clone());
+        self
+    }
+
+    // Identifies the executor's source for resource usage statistics.
+    pub fn source(mut self, source: &str) -> ExecutorInfoBuilder {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    pub fn data(mut self, data: Vec<u8>) -> ExecutorInfoBuilder {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn discovery(mut self,
+                     discovery: DiscoveryInfo)
+                     -> ExecutorInfoBuilder {
+        self.discovery = Some(discovery);
+        self
+    }
+
+    pub fn build(self) -> Result<ExecutorInfo, String> {
+        let executor_id = match self.executor_id {
+            Some(ref id) if !id.get_value().is_empty() => id.clone(),
+            _ => return Err("executor id is required".to_string()),
+        };
+        let command = try!(self.command
+                               .ok_or("executors require a command"));
+
+        let mut executor = ExecutorInfo::new();
+        executor.set_executor_id(executor_id);
+        executor.set_command(command);
+        executor.set_resources(
+            protobuf::RepeatedField::from_vec(self.resources));
+        if let Some(name) = self.name {
+            executor.set_name(name);
+        }
+        if let Some(container) = self.container {
+            executor.set_container(container);
+        }
+        if let Some(framework_id) = self.framework_id {
+            executor.set_framework_id(framework_id);
+        }
+        if let Some(source) = self.source {
+            executor.set_source(source);
+        }
+        if let Some(data) = self.data {
+            executor.set_data(data);
+        }
+        if let Some(discovery) = self.discovery {
+            executor.set_discovery(discovery);
+        }
+        Ok(executor)
+    }
+}
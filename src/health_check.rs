@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use scheduler_client::SchedulerClient;
+use task_registry;
+use proto::mesos::{AgentID, CommandInfo, HealthCheck, HealthCheck_HTTP,
+                   TaskID, TaskStatus};
+
+// Builds a HealthCheck run by the executor, either a command or an HTTP
+// GET against the task.
+#[derive(Clone)]
+pub struct HealthCheckBuilder {
+    health_check: HealthCheck,
+}
+
+impl HealthCheckBuilder {
+    pub fn command(command: CommandInfo) -> HealthCheckBuilder {
+        let mut health_check = HealthCheck::new();
+        health_check.set_command(command);
+        HealthCheckBuilder { health_check: health_check }
+    }
+
+    pub fn http(port: u32, path: &str) -> HealthCheckBuilder {
+        let mut http = HealthCheck_HTTP::new();
+        http.set_port(port);
+        http.set_path(path.to_string());
+
+        let mut health_check = HealthCheck::new();
+        health_check.set_http(http);
+        HealthCheckBuilder { health_check: health_check }
+    }
+
+    // Status codes counted as healthy for HTTP checks.
+    pub fn statuses(mut self, statuses: Vec<u32>) -> HealthCheckBuilder {
+        self.health_check.mut_http().set_statuses(statuses);
+        self
+    }
+
+    pub fn delay_seconds(mut self, seconds: f64) -> HealthCheckBuilder {
+        self.health_check.set_delay_seconds(seconds);
+        self
+    }
+
+    pub fn interval_seconds(mut self, seconds: f64) -> HealthCheckBuilder {
+        self.health_check.set_interval_seconds(seconds);
+        self
+    }
+
+    pub fn timeout_seconds(mut self, seconds: f64) -> HealthCheckBuilder {
+        self.health_check.set_timeout_seconds(seconds);
+        self
+    }
+
+    // Failures during the grace period after launch are not counted.
+    pub fn grace_period_seconds(mut self,
+                                seconds: f64)
+                                -> HealthCheckBuilder {
+        self.health_check.set_grace_period_seconds(seconds);
+        self
+    }
+
+    pub fn consecutive_failures(mut self,
+                                failures: u32)
+                                -> HealthCheckBuilder {
+        self.health_check.set_consecutive_failures(failures);
+        self
+    }
+
+    pub fn build(self) -> Result<HealthCheck, String> {
+        let hc = self.health_check;
+        let durations = [hc.get_delay_seconds(),
+                         hc.get_interval_seconds(),
+                         hc.get_timeout_seconds(),
+                         hc.get_grace_period_seconds()];
+        if durations.iter().any(|d| *d < 0f64) {
+            return Err("health check durations must not be negative"
+                           .to_string());
+        }
+        if hc.has_http() && hc.get_http().get_port() == 0 {
+            return Err("http health checks require a port".to_string());
+        }
+        if hc.has_command() && hc.get_command().get_value().is_empty() {
+            return Err("command health checks require a command"
+                           .to_string());
+        }
+        Ok(hc)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HealthPolicy {
+    // Kill the task, leaving relaunching to the framework's usual
+    // handling of TASK_KILLED.
+    KillAndRelaunch,
+    // Only notify the callback.
+    Notify,
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskHealth {
+    pub task_id: TaskID,
+    pub agent_id: Option<AgentID>,
+    pub healthy: Option<bool>,
+    pub unhealthy_updates: u32,
+}
+
+// Tracks task health from the `healthy` field of status updates, and
+// applies the policy once a task has reported unhealthy max_unhealthy
+// times in a row.  The policy is applied once per unhealthy streak.
+pub struct HealthTracker {
+    pub policy: HealthPolicy,
+    pub max_unhealthy: u32,
+    tasks: HashMap<String, TaskHealth>,
+    on_unhealthy: Option<Box<FnMut(&TaskHealth)>>,
+}
+
+impl HealthTracker {
+    pub fn new(policy: HealthPolicy) -> HealthTracker {
+        HealthTracker {
+            policy: policy,
+            max_unhealthy: 3,
+            tasks: HashMap::new(),
+            on_unhealthy: None,
+        }
+    }
+
+    pub fn on_unhealthy<F>(&mut self, callback: F)
+        where F: FnMut(&TaskHealth) + 'static
+    {
+        self.on_unhealthy = Some(Box::new(callback));
+    }
+
+    // Returns true when this update triggered the policy.
+    pub fn update(&mut self,
+                  client: &SchedulerClient,
+                  status: &TaskStatus)
+                  -> bool {
+        let key = status.get_task_id().get_value().to_string();
+        if task_registry::is_terminal(&status.get_state()) {
+            self.tasks.remove(&key);
+            return false;
+        }
+        if !status.has_healthy() {
+            return false;
+        }
+
+        let health = self.tasks.entry(key).or_insert(TaskHealth {
+            task_id: status.get_task_id().clone(),
+            agent_id: None,
+            healthy: None,
+            unhealthy_updates: 0,
+        });
+        if status.has_agent_id() {
+            health.agent_id = Some(status.get_agent_id().clone());
+        }
+        health.healthy = Some(status.get_healthy());
+        if status.get_healthy() {
+            health.unhealthy_updates = 0;
+            return false;
+        }
+
+        health.unhealthy_updates += 1;
+        if health.unhealthy_updates != self.max_unhealthy {
+            return false;
+        }
+
+        if self.policy == HealthPolicy::KillAndRelaunch {
+            client.kill(health.task_id.clone(), health.agent_id.clone());
+        }
+        if let Some(ref mut callback) = self.on_unhealthy {
+            callback(health);
+        }
+        true
+    }
+
+    pub fn get(&self, task_id: &TaskID) -> Option<&TaskHealth> {
+        self.tasks.get(task_id.get_value())
+    }
+
+    pub fn unhealthy(&self) -> Vec<&TaskHealth> {
+        self.tasks
+            .values()
+            .filter(|h| h.healthy == Some(false))
+            .collect()
+    }
+}
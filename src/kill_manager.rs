@@ -0,0 +1,140 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::mem;
+use std::time::{Duration, Instant};
+
+use scheduler_client::SchedulerClient;
+use task_registry::{self, TaskRecord};
+use proto::mesos::{AgentID, ExecutorID, TaskID, TaskStatus};
+use util;
+
+// A task we asked to kill that has not reached a terminal state yet.
+#[derive(Clone, Debug)]
+pub struct PendingKill {
+    pub task_id: TaskID,
+    pub agent_id: Option<AgentID>,
+    pub executor_id: Option<ExecutorID>,
+    pub attempts: u32,
+    pub shutdown: bool,
+    started: Instant,
+    backoff: Duration,
+    next_attempt: Instant,
+}
+
+// Makes sure killed tasks actually die.  A KILL sent while the agent is
+// partitioned is simply lost, so it is re-sent with exponential backoff
+// until a terminal update arrives.  After shutdown_after the task's
+// executor is shut down as well, when the executor and agent are known,
+// and after give_up_after the task is reported as failed.
+//
+// Call update from Scheduler::update and tick periodically, e.g. on
+// every heartbeat.
+pub struct KillManager {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub shutdown_after: Duration,
+    pub give_up_after: Duration,
+    kills: HashMap<String, PendingKill>,
+    failed: Vec<PendingKill>,
+}
+
+impl KillManager {
+    pub fn new() -> KillManager {
+        KillManager {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            shutdown_after: Duration::from_secs(60),
+            give_up_after: Duration::from_secs(300),
+            kills: HashMap::new(),
+            failed: vec![],
+        }
+    }
+
+    // Sends the first KILL.  Killing a task that is already being killed
+    // only re-sends the KILL.
+    pub fn kill(&mut self,
+                client: &SchedulerClient,
+                task_id: &TaskID,
+                agent_id: Option<&AgentID>,
+                executor_id: Option<&ExecutorID>) {
+        let now = Instant::now();
+        let initial_backoff = self.initial_backoff;
+        let kill = self.kills
+                       .entry(task_id.get_value().to_string())
+                       .or_insert(PendingKill {
+                           task_id: task_id.clone(),
+                           agent_id: agent_id.cloned(),
+                           executor_id: executor_id.cloned(),
+                           attempts: 0,
+                           shutdown: false,
+                           started: now,
+                           backoff: initial_backoff,
+                           next_attempt: now,
+                       });
+        send(client, kill);
+    }
+
+    pub fn kill_task(&mut self, client: &SchedulerClient, task: &TaskRecord) {
+        self.kill(client,
+                  &task.task_id,
+                  task.agent_id.as_ref(),
+                  task.executor_id.as_ref());
+    }
+
+    // Returns true if the update ended a pending kill.
+    pub fn update(&mut self, status: &TaskStatus) -> bool {
+        if !task_registry::is_terminal(&status.get_state()) {
+            return false;
+        }
+        self.kills.remove(status.get_task_id().get_value()).is_some()
+    }
+
+    pub fn tick(&mut self, client: &SchedulerClient) {
+        let now = Instant::now();
+        let mut given_up = vec![];
+        for (key, kill) in self.kills.iter_mut() {
+            let elapsed = now.duration_since(kill.started);
+            if elapsed >= self.give_up_after {
+                given_up.push(key.clone());
+                continue;
+            }
+            if !kill.shutdown && elapsed >= self.shutdown_after {
+                if let (Some(executor_id), Some(agent_id)) =
+                       (kill.executor_id.clone(), kill.agent_id.clone()) {
+                    // Like the KILL, a lost SHUTDOWN is sent again.
+                    kill.shutdown =
+                        util::is_success(&client.shutdown(executor_id,
+                                                          agent_id));
+                }
+            }
+            if now >= kill.next_attempt {
+                kill.backoff = cmp::min(kill.backoff * 2, self.max_backoff);
+                send(client, kill);
+            }
+        }
+        for key in given_up {
+            if let Some(kill) = self.kills.remove(&key) {
+                self.failed.push(kill);
+            }
+        }
+    }
+
+    pub fn is_pending(&self, task_id: &TaskID) -> bool {
+        self.kills.contains_key(task_id.get_value())
+    }
+
+    pub fn pending(&self) -> Vec<&PendingKill> {
+        self.kills.values().collect()
+    }
+
+    // Tasks that did not die within give_up_after.
+    pub fn take_failed(&mut self) -> Vec<PendingKill> {
+        mem::replace(&mut self.failed, vec![])
+    }
+}
+
+fn send(client: &SchedulerClient, kill: &mut PendingKill) {
+    client.kill(kill.task_id.clone(), kill.agent_id.clone());
+    kill.attempts += 1;
+    kill.next_attempt = Instant::now() + kill.backoff;
+}
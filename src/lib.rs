@@ -1,25 +1,73 @@
 #![crate_id = "mesos"]
 #![crate_type = "lib"]
 
+pub mod ack_tracker;
+pub mod batch_scheduler;
+pub mod command_builder;
+pub mod constraints;
+pub mod container_builder;
+pub mod decline_policy;
+pub mod discovery;
 pub mod executor;
+pub mod executor_builder;
 pub mod executor_client;
 pub mod executor_event_stream;
+pub mod health_check;
+pub mod kill_manager;
+pub mod maintenance;
+pub mod offer_inventory;
+pub mod ports;
 pub mod proto;
+pub mod reconciler;
 pub mod recordio;
+pub mod reservation_manager;
+pub mod revive_manager;
+pub mod resources;
+pub mod restart_policy;
 pub mod scheduler;
 pub mod scheduler_client;
 pub mod scheduler_event_stream;
+pub mod scheduler_middleware;
 pub mod scheduler_router;
+pub mod service_scheduler;
+pub mod task_info_builder;
+pub mod task_queue;
+pub mod task_registry;
 pub mod util;
 
+pub use ack_tracker::AckTracker;
+pub use batch_scheduler::{BatchHandle, BatchScheduler};
+pub use command_builder::{CommandBuilder, UriBuilder};
+pub use constraints::{Constraint, PlacedTask};
+pub use container_builder::{DockerContainerBuilder, MesosContainerBuilder};
+pub use decline_policy::{DeclinePolicy, RefuseStrategy};
+pub use discovery::DiscoveryInfoBuilder;
 pub use executor::Executor;
+pub use executor_builder::ExecutorInfoBuilder;
 pub use executor_client::ExecutorClient;
 pub use executor_event_stream::run_protobuf_executor;
+pub use health_check::{HealthCheckBuilder, HealthPolicy, HealthTracker};
+pub use kill_manager::KillManager;
+pub use maintenance::MaintenanceManager;
+pub use offer_inventory::OfferInventory;
+pub use ports::PortAllocator;
+pub use reconciler::Reconciler;
+pub use reservation_manager::ReservationManager;
+pub use resources::Resources;
+pub use restart_policy::{RestartManager, RestartPolicy};
+pub use revive_manager::ReviveManager;
 pub use scheduler::{Scheduler, SchedulerConf};
 pub use scheduler_client::SchedulerClient;
 pub use scheduler_router::{SchedulerRouter, ProtobufCallbackRouter};
 pub use scheduler_event_stream::run_protobuf_scheduler;
+pub use service_scheduler::{AppDefinition, ServiceHandle, ServiceScheduler};
+pub use task_info_builder::TaskInfoBuilder;
+pub use task_queue::{PackingStrategy, TaskQueue};
+pub use task_registry::{TaskRecord, TaskRegistry};
+pub use scheduler_middleware::{CallObserver, MiddlewareRouter,
+                               SchedulerMiddleware};
 
 extern crate hyper;
 extern crate protobuf;
 extern crate itertools;
+extern crate regex;
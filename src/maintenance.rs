@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use offer_inventory;
+use scheduler_client::SchedulerClient;
+use task_registry::{TaskRecord, TaskRegistry};
+use proto::mesos::{AgentID, InverseOffer, Offer, OfferID, Unavailability};
+use util;
+
+// How we answered an inverse offer.  The API has no field for the
+// reason, so it is only kept here, e.g. for logging.
+#[derive(Clone, Debug)]
+pub struct InverseOfferResponse {
+    pub offer_id: OfferID,
+    pub agent_id: AgentID,
+    pub accepted: bool,
+    pub reason: String,
+}
+
+pub type DrainHook = Box<FnMut(&AgentID, &Unavailability, Vec<&TaskRecord>)>;
+
+struct Window {
+    agent_id: AgentID,
+    unavailability: Unavailability,
+    drained: bool,
+}
+
+// Follows the maintenance schedule as the master reveals it, through the
+// Unavailability of inverse offers and offers.
+//
+// Agents with a window overlapping the next `horizon` are draining: no
+// new tasks should be placed on them, see usable, and drain hands their
+// tasks to the drain hook once per window so they can be moved.  Inverse
+// offers are answered with accept, agreeing to vacate the agent in time,
+// or decline.
+pub struct MaintenanceManager {
+    pub horizon: Duration,
+    windows: HashMap<String, Window>,
+    inverse_offers: HashMap<String, InverseOffer>,
+    responses: Vec<InverseOfferResponse>,
+    on_drain: Option<DrainHook>,
+}
+
+impl MaintenanceManager {
+    pub fn new() -> MaintenanceManager {
+        MaintenanceManager {
+            horizon: Duration::from_secs(3600),
+            windows: HashMap::new(),
+            inverse_offers: HashMap::new(),
+            responses: vec![],
+            on_drain: None,
+        }
+    }
+
+    pub fn on_drain<F>(&mut self, callback: F)
+        where F: FnMut(&AgentID, &Unavailability, Vec<&TaskRecord>) + 'static
+    {
+        self.on_drain = Some(Box::new(callback));
+    }
+
+    // Call from Scheduler::inverse_offers.  The inverse offers are held
+    // until answered or rescinded.
+    pub fn inverse_offers(&mut self, inverse_offers: Vec<&InverseOffer>) {
+        for inverse_offer in inverse_offers {
+            self.window(inverse_offer.get_agent_id(),
+                        inverse_offer.get_unavailability());
+            self.inverse_offers
+                .insert(inverse_offer.get_id().get_value().to_string(),
+                        inverse_offer.clone());
+        }
+    }
+
+    // Call from Scheduler::offers.  An offer without unavailability
+    // means the agent's maintenance was cancelled or is over.
+    pub fn offers(&mut self, offers: Vec<&Offer>) {
+        for offer in offers {
+            if offer.has_unavailability() {
+                self.window(offer.get_agent_id(), offer.get_unavailability());
+            } else {
+                self.windows.remove(offer.get_agent_id().get_value());
+            }
+        }
+    }
+
+    pub fn rescind(&mut self, offer_id: &OfferID) -> Option<InverseOffer> {
+        self.inverse_offers.remove(offer_id.get_value())
+    }
+
+    pub fn inverse_offer(&self, offer_id: &OfferID) -> Option<&InverseOffer> {
+        self.inverse_offers.get(offer_id.get_value())
+    }
+
+    pub fn pending_inverse_offers(&self) -> Vec<&InverseOffer> {
+        self.inverse_offers.values().collect()
+    }
+
+    // Agrees to the maintenance.  Returns false if the inverse offer is
+    // not outstanding, or the master did not take the answer; the
+    // inverse offer then stays pending so it can be answered again.
+    pub fn accept(&mut self,
+                  client: &SchedulerClient,
+                  offer_id: &OfferID,
+                  reason: &str)
+                  -> bool {
+        if self.inverse_offer(offer_id).is_none() ||
+           !util::is_success(&client.accept(vec![offer_id.clone()],
+                                            vec![],
+                                            None)) {
+            return false;
+        }
+        self.respond(offer_id, true, reason)
+    }
+
+    pub fn decline(&mut self,
+                   client: &SchedulerClient,
+                   offer_id: &OfferID,
+                   reason: &str)
+                   -> bool {
+        if self.inverse_offer(offer_id).is_none() ||
+           !util::is_success(&client.decline(vec![offer_id.clone()], None)) {
+            return false;
+        }
+        self.respond(offer_id, false, reason)
+    }
+
+    pub fn responses(&self) -> &[InverseOfferResponse] {
+        &self.responses
+    }
+
+    pub fn unavailability(&self,
+                          agent_id: &AgentID)
+                          -> Option<&Unavailability> {
+        self.windows.get(agent_id.get_value()).map(|w| &w.unavailability)
+    }
+
+    pub fn is_draining(&self, agent_id: &AgentID) -> bool {
+        let now = offer_inventory::now_nanos();
+        self.windows.get(agent_id.get_value()).map_or(false, |w| {
+            offer_inventory::unavailable_within(&w.unavailability,
+                                                now,
+                                                self.horizon)
+        })
+    }
+
+    // The offers that new tasks may be placed on.
+    pub fn usable<'a>(&self, offers: Vec<&'a Offer>) -> Vec<&'a Offer> {
+        offers.into_iter()
+              .filter(|o| !self.is_draining(o.get_agent_id()))
+              .collect()
+    }
+
+    // Calls the drain hook with the non-terminal tasks of every agent
+    // that started draining since the last call, returning those agents.
+    pub fn drain(&mut self, registry: &TaskRegistry) -> Vec<AgentID> {
+        let draining: Vec<String> = self.windows
+                                        .iter()
+                                        .filter(|&(_, w)| {
+                                            !w.drained &&
+                                            self.is_draining(&w.agent_id)
+                                        })
+                                        .map(|(agent, _)| agent.clone())
+                                        .collect();
+
+        let mut drained = vec![];
+        for agent in draining {
+            let window = self.windows.get_mut(&agent).unwrap();
+            window.drained = true;
+            if let Some(ref mut callback) = self.on_drain {
+                callback(&window.agent_id,
+                         &window.unavailability,
+                         registry.on_agent(&window.agent_id)
+                                 .into_iter()
+                                 .filter(|r| !r.is_terminal())
+                                 .collect());
+            }
+            drained.push(window.agent_id.clone());
+        }
+        drained
+    }
+
+    fn window(&mut self, agent_id: &AgentID, unavailability: &Unavailability) {
+        let key = agent_id.get_value().to_string();
+        if let Some(window) = self.windows.get(&key) {
+            if window.unavailability == *unavailability {
+                return;
+            }
+        }
+        // A new or moved window is drained again.
+        self.windows.insert(key,
+                            Window {
+                                agent_id: agent_id.clone(),
+                                unavailability: unavailability.clone(),
+                                drained: false,
+                            });
+    }
+
+    fn respond(&mut self,
+               offer_id: &OfferID,
+               accepted: bool,
+               reason: &str)
+               -> bool {
+        let inverse_offer = match self.inverse_offers
+                                      .remove(offer_id.get_value()) {
+            Some(inverse_offer) => inverse_offer,
+            None => return false,
+        };
+        self.responses.push(InverseOfferResponse {
+            offer_id: offer_id.clone(),
+            agent_id: inverse_offer.get_agent_id().clone(),
+            accepted: accepted,
+            reason: reason.to_string(),
+        });
+        true
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hyper;
+use hyper::client::response::Response;
+
+use scheduler_client::SchedulerClient;
+use proto::mesos::{AgentID, Filters, Offer, OfferID, Operation, TaskInfo,
+                   Unavailability};
+use util;
+
+// Nanoseconds since the epoch, the unit of Unavailability windows.
+pub fn now_nanos() -> i64 {
+    let elapsed = SystemTime::now()
+                      .duration_since(UNIX_EPOCH)
+                      .unwrap_or(Duration::from_secs(0));
+    elapsed.as_secs() as i64 * 1_000_000_000 + elapsed.subsec_nanos() as i64
+}
+
+// True if the window overlaps [now, now + horizon].  A window without a
+// duration lasts forever.
+pub fn unavailable_within(unavailability: &Unavailability,
+                          now: i64,
+                          horizon: Duration)
+                          -> bool {
+    let horizon = horizon.as_secs() as i64 * 1_000_000_000 +
+                  horizon.subsec_nanos() as i64;
+    let start = unavailability.get_start().get_nanoseconds();
+    if start > now + horizon {
+        return false;
+    }
+    !unavailability.has_duration() ||
+    start + unavailability.get_duration().get_nanoseconds() > now
+}
+
+struct HeldOffer {
+    offer: Offer,
+    received: Instant,
+}
+
+// Keeps the offers a scheduler is holding on to, so that launches can be
+// batched without using stale offers.  Call add from Scheduler::offers,
+// rescind from Scheduler::rescind, clear on disconnection, and tick
+// periodically to decline offers held longer than max_hold.
+//
+// Offers leave the inventory as they are accepted or declined through
+// it, so an offer can never be used by two ACCEPT calls.
+pub struct OfferInventory {
+    pub max_hold: Duration,
+    // Used when declining offers that expired.
+    pub refuse_seconds: f64,
+    // Offers from agents scheduled to be unavailable within this long
+    // are not handed out by available.
+    pub unavailability_horizon: Duration,
+    offers: Vec<HeldOffer>,
+}
+
+impl OfferInventory {
+    pub fn new() -> OfferInventory {
+        OfferInventory {
+            max_hold: Duration::from_secs(30),
+            refuse_seconds: 5f64,
+            unavailability_horizon: Duration::from_secs(0),
+            offers: vec![],
+        }
+    }
+
+    pub fn add(&mut self, offers: Vec<&Offer>) {
+        let now = Instant::now();
+        for offer in offers {
+            if self.get(offer.get_id()).is_none() {
+                self.offers.push(HeldOffer {
+                    offer: offer.clone(),
+                    received: now,
+                });
+            }
+        }
+    }
+
+    pub fn rescind(&mut self, offer_id: &OfferID) -> Option<Offer> {
+        self.position(offer_id).map(|idx| self.offers.remove(idx).offer)
+    }
+
+    // Offers do not survive a disconnection from the master.
+    pub fn clear(&mut self) {
+        self.offers.clear();
+    }
+
+    pub fn get(&self, offer_id: &OfferID) -> Option<&Offer> {
+        self.position(offer_id).map(|idx| &self.offers[idx].offer)
+    }
+
+    pub fn len(&self) -> usize {
+        self.offers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offers.is_empty()
+    }
+
+    // Every held offer, in the order received.
+    pub fn offers(&self) -> Vec<&Offer> {
+        self.offers.iter().map(|h| &h.offer).collect()
+    }
+
+    // Held offers whose agent is not scheduled to be unavailable within
+    // unavailability_horizon.
+    pub fn available(&self) -> Vec<&Offer> {
+        let now = now_nanos();
+        self.offers
+            .iter()
+            .map(|h| &h.offer)
+            .filter(|o| {
+                !o.has_unavailability() ||
+                !unavailable_within(o.get_unavailability(),
+                                    now,
+                                    self.unavailability_horizon)
+            })
+            .collect()
+    }
+
+    pub fn on_agent(&self, agent_id: &AgentID) -> Vec<&Offer> {
+        self.offers
+            .iter()
+            .map(|h| &h.offer)
+            .filter(|o| o.get_agent_id().get_value() == agent_id.get_value())
+            .collect()
+    }
+
+    // Removes the offers, all or nothing.  Returns None if any of them
+    // was already taken, rescinded or expired.
+    pub fn take(&mut self, offer_ids: &[OfferID]) -> Option<Vec<Offer>> {
+        let mut seen: Vec<&str> = vec![];
+        for offer_id in offer_ids {
+            if self.get(offer_id).is_none() ||
+               seen.contains(&offer_id.get_value()) {
+                return None;
+            }
+            seen.push(offer_id.get_value());
+        }
+        Some(offer_ids.iter()
+                      .filter_map(|id| self.rescind(id))
+                      .collect())
+    }
+
+    // Sends the ACCEPT only if every offer is still held.  Returns None
+    // if it was not sent, otherwise the master's response.  The offers
+    // leave the inventory either way, since a failed ACCEPT may still
+    // have reached the master.
+    pub fn accept(&mut self,
+                  client: &SchedulerClient,
+                  offer_ids: Vec<OfferID>,
+                  operations: Vec<Operation>,
+                  filters: Option<Filters>)
+                  -> Option<hyper::Result<Response>> {
+        if self.take(&offer_ids).is_none() {
+            return None;
+        }
+        Some(client.accept(offer_ids, operations, filters))
+    }
+
+    pub fn launch(&mut self,
+                  client: &SchedulerClient,
+                  offer_ids: Vec<OfferID>,
+                  tasks: Vec<TaskInfo>,
+                  filters: Option<Filters>)
+                  -> Option<hyper::Result<Response>> {
+        if self.take(&offer_ids).is_none() {
+            return None;
+        }
+        Some(client.launch(offer_ids, tasks, filters))
+    }
+
+    // Declines whichever of the offers are still held.
+    pub fn decline(&mut self,
+                   client: &SchedulerClient,
+                   offer_ids: Vec<OfferID>,
+                   filters: Option<Filters>) {
+        let held: Vec<OfferID> = offer_ids.into_iter()
+                                          .filter(|id| {
+                                              self.rescind(id).is_some()
+                                          })
+                                          .collect();
+        if held.len() > 0 {
+            client.decline(held, filters);
+        }
+    }
+
+    // Declines offers held longer than max_hold, returning their ids.
+    pub fn tick(&mut self, client: &SchedulerClient) -> Vec<OfferID> {
+        let max_hold = self.max_hold;
+        let expired: Vec<OfferID> = self.offers
+                                        .iter()
+                                        .filter(|h| {
+                                            h.received.elapsed() >= max_hold
+                                        })
+                                        .map(|h| h.offer.get_id().clone())
+                                        .collect();
+        if expired.len() > 0 {
+            let filters = util::filters(self.refuse_seconds);
+            self.decline(client, expired.clone(), Some(filters));
+        }
+        expired
+    }
+
+    fn position(&self, offer_id: &OfferID) -> Option<usize> {
+        self.offers
+            .iter()
+            .position(|h| h.offer.get_id().get_value() == offer_id.get_value())
+    }
+}
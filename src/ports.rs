@@ -0,0 +1,111 @@
+use resources::{self, Resources};
+use proto::mesos::{Offer, Resource, Value_Type};
+use util;
+
+// Carves ports out of the "ports" ranges of an offer.  Each allocation
+// removes the ports from what is left, so several tasks can be launched
+// from the same offer without conflicting.
+pub struct PortAllocator {
+    template: Resource,
+    available: Vec<(u64, u64)>,
+}
+
+impl PortAllocator {
+    pub fn new(role: &str, ranges: Vec<(u64, u64)>) -> PortAllocator {
+        PortAllocator {
+            template: util::ranges("ports", role, vec![]),
+            available: resources::coalesce(ranges),
+        }
+    }
+
+    // Uses the ports offered to the given role.  Reservation information
+    // on the offered resource is kept on the resources we hand out.
+    pub fn from_offer(offer: &Offer, role: &str) -> PortAllocator {
+        PortAllocator::from_resources(&Resources::from_offer(offer), role)
+    }
+
+    pub fn from_resources(resources: &Resources,
+                          role: &str)
+                          -> PortAllocator {
+        let offered = resources.named("ports").role(role);
+        let mut allocator = PortAllocator::new(role,
+                                               offered.ranges("ports"));
+        if let Some(resource) = offered.as_slice()
+                                       .iter()
+                                       .find(|r| {
+                                           r.get_field_type() ==
+                                           Value_Type::RANGES
+                                       }) {
+            allocator.template = resource.clone();
+        }
+        allocator
+    }
+
+    pub fn available(&self) -> &[(u64, u64)] {
+        &self.available
+    }
+
+    pub fn count(&self) -> u64 {
+        self.available.iter().fold(0, |acc, &(b, e)| acc + e - b + 1)
+    }
+
+    // Takes the lowest n available ports, or nothing if there are not
+    // enough.
+    pub fn allocate(&mut self, n: usize) -> Option<Vec<u64>> {
+        if (n as u64) > self.count() {
+            return None;
+        }
+        let mut ports = Vec::with_capacity(n);
+        for &(begin, end) in &self.available {
+            let mut port = begin;
+            while port <= end && ports.len() < n {
+                ports.push(port);
+                port += 1;
+            }
+            if ports.len() == n {
+                break;
+            }
+        }
+        self.remove(&ports);
+        Some(ports)
+    }
+
+    // Takes exactly the requested ports, or nothing if any of them is
+    // unavailable.
+    pub fn allocate_specific(&mut self, ports: &[u64]) -> Option<Vec<u64>> {
+        let all_available = ports.iter().all(|&p| {
+            self.available.iter().any(|&(b, e)| b <= p && p <= e)
+        });
+        let mut unique = ports.to_vec();
+        unique.sort();
+        unique.dedup();
+        if !all_available || unique.len() != ports.len() {
+            return None;
+        }
+        self.remove(ports);
+        Some(ports.to_vec())
+    }
+
+    // The "ports" resource to put in the TaskInfo for these ports.
+    pub fn resource(&self, ports: &[u64]) -> Resource {
+        let ranges = resources::coalesce(ports.iter()
+                                              .map(|&p| (p, p))
+                                              .collect());
+        let mut resource = self.template.clone();
+        resource.set_ranges(resources::from_pairs(ranges));
+        resource
+    }
+
+    // The ports still available, as a resource.
+    pub fn remaining(&self) -> Resource {
+        let mut resource = self.template.clone();
+        resource.set_ranges(resources::from_pairs(self.available.clone()));
+        resource
+    }
+
+    fn remove(&mut self, ports: &[u64]) {
+        let taken: Vec<(u64, u64)> = ports.iter().map(|&p| (p, p)).collect();
+        self.available = resources::subtract_ranges(self.available.clone(),
+                                                    &taken);
+    }
+}
@@ -0,0 +1,140 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use scheduler_client::SchedulerClient;
+use task_registry::TaskRegistry;
+use util;
+use proto::mesos::{AgentID, TaskID, TaskStatus};
+use proto::scheduler::Call_Reconcile_Task;
+
+// Drives task reconciliation after each subscription, following the
+// algorithm recommended by Mesos:
+//
+// 1. explicitly reconcile every non-terminal task we know about,
+// 2. re-send explicit reconciliation for tasks that have not received an
+//    update yet, backing off exponentially between attempts,
+// 3. once every task is confirmed, issue an implicit reconciliation to
+//    learn about tasks we don't know about, retrying it with the same
+//    backoff until the master accepts it.
+//
+// Call subscribed from Scheduler::subscribed, update from
+// Scheduler::update and tick periodically, e.g. on every heartbeat.
+pub struct Reconciler {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    unconfirmed: HashMap<String, (TaskID, Option<AgentID>)>,
+    backoff: Duration,
+    next_attempt: Option<Instant>,
+    converged: bool,
+}
+
+impl Reconciler {
+    pub fn new() -> Reconciler {
+        Reconciler {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            unconfirmed: HashMap::new(),
+            backoff: Duration::from_secs(1),
+            next_attempt: None,
+            converged: false,
+        }
+    }
+
+    pub fn subscribed(&mut self,
+                      client: &SchedulerClient,
+                      tasks: Vec<(TaskID, Option<AgentID>)>) {
+        self.unconfirmed = tasks.into_iter()
+                                .map(|(t, a)| {
+                                    (t.get_value().to_string(), (t, a))
+                                })
+                                .collect();
+        self.backoff = self.initial_backoff;
+        self.converged = false;
+
+        if self.unconfirmed.is_empty() {
+            self.converge(client);
+        } else {
+            self.send(client);
+        }
+    }
+
+    // Reconciles every non-terminal task in the registry.
+    pub fn subscribed_with_registry(&mut self,
+                                    client: &SchedulerClient,
+                                    registry: &TaskRegistry) {
+        let tasks = registry.non_terminal()
+                            .into_iter()
+                            .map(|r| (r.task_id.clone(), r.agent_id.clone()))
+                            .collect();
+        self.subscribed(client, tasks);
+    }
+
+    // Any update confirms the task's state, whether it is a
+    // REASON_RECONCILIATION response or a regular update that crossed
+    // paths with our request.  Returns true when this update completed
+    // reconciliation.
+    pub fn update(&mut self,
+                  client: &SchedulerClient,
+                  status: &TaskStatus)
+                  -> bool {
+        if self.converged {
+            return false;
+        }
+        self.unconfirmed.remove(status.get_task_id().get_value());
+        if self.unconfirmed.is_empty() && self.next_attempt.is_some() {
+            self.converge(client);
+            return self.converged;
+        }
+        false
+    }
+
+    // Re-sends explicit reconciliation, or the implicit one if it failed,
+    // once the current backoff has elapsed.
+    pub fn tick(&mut self, client: &SchedulerClient) {
+        match self.next_attempt {
+            Some(at) if Instant::now() >= at => {
+                self.backoff = cmp::min(self.backoff * 2, self.max_backoff);
+                if self.unconfirmed.is_empty() {
+                    self.converge(client);
+                } else {
+                    self.send(client);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    pub fn is_converged(&self) -> bool {
+        self.converged
+    }
+
+    pub fn unconfirmed(&self) -> Vec<&TaskID> {
+        self.unconfirmed.values().map(|&(ref t, _)| t).collect()
+    }
+
+    fn send(&mut self, client: &SchedulerClient) {
+        let tasks = self.unconfirmed
+                        .values()
+                        .map(|&(ref task_id, ref agent_id)| {
+                            let mut task = Call_Reconcile_Task::new();
+                            task.set_task_id(task_id.clone());
+                            if let Some(ref agent_id) = *agent_id {
+                                task.set_agent_id(agent_id.clone());
+                            }
+                            task
+                        })
+                        .collect();
+        client.reconcile(tasks);
+        self.next_attempt = Some(Instant::now() + self.backoff);
+    }
+
+    fn converge(&mut self, client: &SchedulerClient) {
+        if util::is_success(&client.reconcile(vec![])) {
+            self.next_attempt = None;
+            self.converged = true;
+        } else {
+            self.next_attempt = Some(Instant::now() + self.backoff);
+        }
+    }
+}
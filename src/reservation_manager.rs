@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+
+use resources::Resources;
+use proto::mesos::{AgentID, Offer, Operation, Resource, Volume_Mode};
+use util;
+
+// What a stateful instance needs from an agent.  Resources are written
+// unreserved; the manager reserves them for its role.
+#[derive(Clone, Debug)]
+pub struct InstanceSpec {
+    pub resources: Vec<Resource>,
+    pub volume_size: f64,
+    pub container_path: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstanceState {
+    // Nothing reserved yet, waiting for an offer with enough unreserved
+    // resources.
+    Unplaced,
+    // A task is running on the reserved resources.
+    Running,
+    // Resources and volume are reserved, but no task is running on them.
+    Stopped,
+    // Waiting for an offer to destroy the volume and unreserve.
+    Decommissioning,
+    // The volume is destroyed, but the reserved resources were not in
+    // the same offer; waiting for an offer to unreserve them.
+    Unreserving,
+}
+
+#[derive(Clone, Debug)]
+pub struct StatefulInstance {
+    pub name: String,
+    pub persistence_id: String,
+    pub agent_id: Option<AgentID>,
+    pub state: InstanceState,
+    pub spec: InstanceSpec,
+    // The state before the latest placement, until its task is stopped.
+    placed_from: Option<InstanceState>,
+}
+
+// Operations to send for one instance in an ACCEPT.  When resources is
+// non-empty, add a LAUNCH for the instance's task using exactly these
+// resources, after the operations, and report a failed ACCEPT with
+// ReservationManager::launch_failed.
+#[derive(Clone, Debug)]
+pub struct Placement {
+    pub instance: String,
+    pub agent_id: AgentID,
+    pub operations: Vec<Operation>,
+    pub resources: Vec<Resource>,
+}
+
+// Tracks reserved resources and persistent volumes for logical
+// instances of a stateful service, so that a lost instance is relaunched
+// on the same agent with the same volume.
+//
+// Reservations carry no instance identity in this API version, so
+// instances on one agent share the reserved pool for the role and
+// principal.  Volumes are told apart by their persistence id.
+pub struct ReservationManager {
+    pub role: String,
+    pub principal: String,
+    instances: HashMap<String, StatefulInstance>,
+}
+
+impl ReservationManager {
+    pub fn new(role: &str, principal: &str) -> ReservationManager {
+        ReservationManager {
+            role: role.to_string(),
+            principal: principal.to_string(),
+            instances: HashMap::new(),
+        }
+    }
+
+    pub fn add_instance(&mut self, name: &str, spec: InstanceSpec) {
+        self.instances.insert(name.to_string(),
+                              StatefulInstance {
+                                  name: name.to_string(),
+                                  persistence_id: name.to_string(),
+                                  agent_id: None,
+                                  state: InstanceState::Unplaced,
+                                  spec: spec,
+                                  placed_from: None,
+                              });
+    }
+
+    // Re-registers an instance whose volume already exists, e.g. after
+    // a scheduler failover.  If the agent is unknown it is learned from
+    // the first offer carrying the volume.
+    pub fn restore(&mut self,
+                   name: &str,
+                   spec: InstanceSpec,
+                   persistence_id: &str,
+                   agent_id: Option<AgentID>) {
+        self.instances.insert(name.to_string(),
+                              StatefulInstance {
+                                  name: name.to_string(),
+                                  persistence_id: persistence_id.to_string(),
+                                  agent_id: agent_id,
+                                  state: InstanceState::Stopped,
+                                  spec: spec,
+                                  placed_from: None,
+                              });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StatefulInstance> {
+        self.instances.get(name)
+    }
+
+    pub fn instances(&self) -> Vec<&StatefulInstance> {
+        self.instances.values().collect()
+    }
+
+    // The instance's task has terminated; relaunch it on its
+    // reservation when the agent offers it again.
+    pub fn stopped(&mut self, name: &str) {
+        if let Some(instance) = self.instances.get_mut(name) {
+            if instance.state == InstanceState::Running {
+                instance.state = InstanceState::Stopped;
+                instance.placed_from = None;
+            }
+        }
+    }
+
+    // The ACCEPT for the instance's placement never reached the master.
+    // A fresh reservation is made again on the next suitable offer, from
+    // any agent; an instance relaunching on its volume waits for it to be
+    // offered again.
+    pub fn launch_failed(&mut self, name: &str) {
+        if let Some(instance) = self.instances.get_mut(name) {
+            match instance.placed_from.take() {
+                Some(InstanceState::Unplaced) => {
+                    instance.state = InstanceState::Unplaced;
+                    instance.agent_id = None;
+                }
+                Some(state) => instance.state = state,
+                None => (),
+            }
+        }
+    }
+
+    // Destroys the volume and releases the reservation the next time
+    // the agent offers them.  The task must already be stopped.
+    pub fn decommission(&mut self, name: &str) {
+        let remove = match self.instances.get_mut(name) {
+            Some(instance) => {
+                if instance.state == InstanceState::Unplaced {
+                    true
+                } else {
+                    instance.state = InstanceState::Decommissioning;
+                    false
+                }
+            }
+            None => false,
+        };
+        if remove {
+            self.instances.remove(name);
+        }
+    }
+
+    // Decides what to do with an offer.  Instances already bound to the
+    // offer's agent are handled first, then unplaced instances are
+    // reserved on whatever is left.
+    pub fn plan(&mut self, offer: &Offer) -> Vec<Placement> {
+        let agent_id = offer.get_agent_id();
+        let mut available = Resources::from_offer(offer);
+        let mut placements = vec![];
+        let mut finished = vec![];
+
+        let mut names: Vec<String> = self.instances.keys().cloned().collect();
+        names.sort();
+
+        for name in &names {
+            let instance = self.instances.get_mut(name).unwrap();
+
+            if instance.state == InstanceState::Unreserving {
+                let on_agent = instance.agent_id
+                                       .as_ref()
+                                       .map_or(false, |a| {
+                                           a.get_value() ==
+                                           agent_id.get_value()
+                                       });
+                let reserved = reserve_all(&instance.spec.resources,
+                                           &self.role,
+                                           &self.principal);
+                if on_agent && available.contains(&reserved) {
+                    available = available - reserved.clone();
+                    let operations =
+                        vec![util::unreserve_operation(reserved.into_vec())];
+                    placements.push(Placement {
+                        instance: name.clone(),
+                        agent_id: agent_id.clone(),
+                        operations: operations,
+                        resources: vec![],
+                    });
+                    finished.push(name.clone());
+                }
+                continue;
+            }
+
+            let volume = available.persistent_volumes()
+                                  .as_slice()
+                                  .iter()
+                                  .find(|v| {
+                                      v.get_disk()
+                                       .get_persistence()
+                                       .get_id() ==
+                                      instance.persistence_id
+                                  })
+                                  .cloned();
+            let volume = match volume {
+                Some(volume) => volume,
+                None => continue,
+            };
+
+            let on_agent = instance.agent_id
+                                   .as_ref()
+                                   .map_or(true, |a| {
+                                       a.get_value() == agent_id.get_value()
+                                   });
+            if !on_agent {
+                continue;
+            }
+            instance.agent_id = Some(agent_id.clone());
+
+            let reserved = reserve_all(&instance.spec.resources,
+                                       &self.role,
+                                       &self.principal);
+
+            match instance.state {
+                InstanceState::Stopped => {
+                    if !available.contains(&reserved) {
+                        continue;
+                    }
+                    available = available - reserved.clone();
+                    available.subtract_resource(&volume);
+
+                    let mut resources = reserved.into_vec();
+                    resources.push(volume);
+
+                    instance.state = InstanceState::Running;
+                    instance.placed_from = Some(InstanceState::Stopped);
+                    placements.push(Placement {
+                        instance: name.clone(),
+                        agent_id: agent_id.clone(),
+                        operations: vec![],
+                        resources: resources,
+                    });
+                }
+                InstanceState::Decommissioning => {
+                    available.subtract_resource(&volume);
+
+                    let mut unreserve = vec![unreserved_disk(&volume)];
+                    if available.contains(&reserved) {
+                        available = available - reserved.clone();
+                        unreserve.extend(reserved.into_vec());
+                        finished.push(name.clone());
+                    } else {
+                        instance.state = InstanceState::Unreserving;
+                    }
+
+                    let operations =
+                        vec![util::destroy_operation(vec![volume]),
+                             util::unreserve_operation(unreserve)];
+                    placements.push(Placement {
+                        instance: name.clone(),
+                        agent_id: agent_id.clone(),
+                        operations: operations,
+                        resources: vec![],
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        for name in &names {
+            let instance = self.instances.get_mut(name).unwrap();
+            if instance.state != InstanceState::Unplaced {
+                continue;
+            }
+
+            let mut needed = Resources::from_vec(instance.spec
+                                                         .resources
+                                                         .clone());
+            needed.add_resource(util::scalar("disk",
+                                             "*",
+                                             instance.spec.volume_size));
+            if !available.unreserved().contains(&needed) {
+                continue;
+            }
+            available = available - needed;
+
+            let reserved = reserve_all(&instance.spec.resources,
+                                       &self.role,
+                                       &self.principal);
+            let disk = util::reserved(util::scalar("disk",
+                                                   "*",
+                                                   instance.spec.volume_size),
+                                      &self.role,
+                                      &self.principal);
+            let volume = util::persistent_volume(instance.spec.volume_size,
+                                                 &self.role,
+                                                 &self.principal,
+                                                 &instance.persistence_id,
+                                                 &instance.spec
+                                                          .container_path,
+                                                 Volume_Mode::RW);
+
+            let mut reserve = reserved.clone().into_vec();
+            reserve.push(disk);
+
+            let mut resources = reserved.into_vec();
+            resources.push(volume.clone());
+
+            instance.agent_id = Some(agent_id.clone());
+            instance.state = InstanceState::Running;
+            instance.placed_from = Some(InstanceState::Unplaced);
+            placements.push(Placement {
+                instance: name.clone(),
+                agent_id: agent_id.clone(),
+                operations: vec![util::reserve_operation(reserve),
+                                 util::create_operation(vec![volume])],
+                resources: resources,
+            });
+        }
+
+        for name in finished {
+            self.instances.remove(&name);
+        }
+        placements
+    }
+}
+
+fn reserve_all(resources: &[Resource],
+               role: &str,
+               principal: &str)
+               -> Resources {
+    Resources::from_vec(resources.iter()
+                                 .map(|r| {
+                                     util::reserved(r.clone(),
+                                                    role,
+                                                    principal)
+                                 })
+                                 .collect())
+}
+
+// The reserved disk left behind once a volume is destroyed.
+fn unreserved_disk(volume: &Resource) -> Resource {
+    let mut disk = volume.clone();
+    disk.clear_disk();
+    disk
+}
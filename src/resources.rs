@@ -0,0 +1,437 @@
+use std::ops::{Add, Sub};
+
+use protobuf;
+
+use proto::mesos::{Offer, Resource, Value_Range, Value_Ranges, Value_Set,
+                   Value_Type};
+
+// Scalars are doubles on the wire, so allow for rounding when comparing.
+const EPSILON: f64 = 0.0001;
+
+// A collection of resources supporting the arithmetic needed to match
+// tasks against offers.
+//
+// Resources with the same name, type, role, reservation, disk and
+// revocability are merged: scalars are summed, ranges are unioned and
+// sets are joined.  Persistent volumes are never merged, since each one
+// is a distinct volume.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resources {
+    resources: Vec<Resource>,
+}
+
+impl Resources {
+    pub fn new() -> Resources {
+        Resources { resources: vec![] }
+    }
+
+    pub fn from_vec(resources: Vec<Resource>) -> Resources {
+        let mut result = Resources::new();
+        for resource in resources {
+            result.add_resource(resource);
+        }
+        result
+    }
+
+    pub fn from_offer(offer: &Offer) -> Resources {
+        Resources::from_vec(offer.get_resources().to_vec())
+    }
+
+    pub fn from_offers(offers: &[&Offer]) -> Resources {
+        Resources::from_vec(offers.iter()
+                                  .flat_map(|o| o.get_resources())
+                                  .cloned()
+                                  .collect())
+    }
+
+    pub fn into_vec(self) -> Vec<Resource> {
+        self.resources
+    }
+
+    pub fn as_slice(&self) -> &[Resource] {
+        &self.resources
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    pub fn add_resource(&mut self, mut resource: Resource) {
+        if is_empty(&resource) {
+            return;
+        }
+        normalize(&mut resource);
+        if !is_persistent_volume(&resource) {
+            for existing in self.resources.iter_mut() {
+                if addable(existing, &resource) {
+                    merge(existing, &resource);
+                    return;
+                }
+            }
+        }
+        self.resources.push(resource);
+    }
+
+    // Returns false, leaving self untouched, when the resource is not
+    // fully contained.
+    pub fn subtract_resource(&mut self, resource: &Resource) -> bool {
+        if is_empty(resource) {
+            return true;
+        }
+        let idx = match self.resources
+                            .iter()
+                            .position(|r| contains_resource(r, resource)) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        if is_persistent_volume(resource) {
+            self.resources.remove(idx);
+            return true;
+        }
+        remove(&mut self.resources[idx], resource);
+        if is_empty(&self.resources[idx]) {
+            self.resources.remove(idx);
+        }
+        true
+    }
+
+    pub fn contains(&self, other: &Resources) -> bool {
+        let mut remaining = self.clone();
+        other.resources.iter().all(|r| remaining.subtract_resource(r))
+    }
+
+    pub fn filter<F>(&self, predicate: F) -> Resources
+        where F: Fn(&Resource) -> bool
+    {
+        Resources {
+            resources: self.resources
+                           .iter()
+                           .filter(|r| predicate(r))
+                           .cloned()
+                           .collect(),
+        }
+    }
+
+    pub fn named(&self, name: &str) -> Resources {
+        self.filter(|r| r.get_name() == name)
+    }
+
+    pub fn role(&self, role: &str) -> Resources {
+        self.filter(|r| r.get_role() == role)
+    }
+
+    pub fn reserved(&self) -> Resources {
+        self.filter(|r| r.get_role() != "*")
+    }
+
+    pub fn unreserved(&self) -> Resources {
+        self.filter(|r| r.get_role() == "*")
+    }
+
+    // Resources dynamically reserved by the given principal.
+    pub fn reserved_by(&self, principal: &str) -> Resources {
+        self.filter(|r| {
+            r.has_reservation() &&
+            r.get_reservation().get_principal() == principal
+        })
+    }
+
+    pub fn revocable(&self) -> Resources {
+        self.filter(|r| r.has_revocable())
+    }
+
+    pub fn non_revocable(&self) -> Resources {
+        self.filter(|r| !r.has_revocable())
+    }
+
+    pub fn persistent_volumes(&self) -> Resources {
+        self.filter(is_persistent_volume)
+    }
+
+    // Moves every resource to the given role, dropping reservation
+    // information.  Useful for comparing requirements written against
+    // "*" with offers that carry reserved resources.
+    pub fn flatten(&self, role: &str) -> Resources {
+        let mut result = Resources::new();
+        for resource in &self.resources {
+            let mut resource = resource.clone();
+            resource.set_role(role.to_string());
+            resource.clear_reservation();
+            result.add_resource(resource);
+        }
+        result
+    }
+
+    pub fn scalar(&self, name: &str) -> f64 {
+        self.resources
+            .iter()
+            .filter(|r| {
+                r.get_name() == name &&
+                r.get_field_type() == Value_Type::SCALAR
+            })
+            .fold(0f64, |acc, r| acc + r.get_scalar().get_value())
+    }
+
+    // All ranges for the given name across roles, coalesced.
+    pub fn ranges(&self, name: &str) -> Vec<(u64, u64)> {
+        let ranges = self.resources
+                         .iter()
+                         .filter(|r| {
+                             r.get_name() == name &&
+                             r.get_field_type() == Value_Type::RANGES
+                         })
+                         .flat_map(|r| to_pairs(r.get_ranges()))
+                         .collect();
+        coalesce(ranges)
+    }
+
+    pub fn set(&self, name: &str) -> Vec<String> {
+        let mut items: Vec<String> = vec![];
+        for r in &self.resources {
+            if r.get_name() == name && r.get_field_type() == Value_Type::SET {
+                for item in r.get_set().get_item() {
+                    if !items.contains(item) {
+                        items.push(item.clone());
+                    }
+                }
+            }
+        }
+        items
+    }
+}
+
+impl Add for Resources {
+    type Output = Resources;
+
+    fn add(mut self, other: Resources) -> Resources {
+        for resource in other.resources {
+            self.add_resource(resource);
+        }
+        self
+    }
+}
+
+// Subtracts whatever part of each resource is present; resources that
+// are not contained are ignored.
+impl Sub for Resources {
+    type Output = Resources;
+
+    fn sub(mut self, other: Resources) -> Resources {
+        for resource in &other.resources {
+            if !self.subtract_resource(resource) {
+                remove_overlap(&mut self.resources, resource);
+            }
+        }
+        self
+    }
+}
+
+pub fn is_persistent_volume(resource: &Resource) -> bool {
+    resource.has_disk() && resource.get_disk().has_persistence()
+}
+
+fn addable(left: &Resource, right: &Resource) -> bool {
+    left.get_name() == right.get_name() &&
+    left.get_field_type() == right.get_field_type() &&
+    left.get_role() == right.get_role() &&
+    left.has_reservation() == right.has_reservation() &&
+    left.get_reservation() == right.get_reservation() &&
+    left.has_disk() == right.has_disk() &&
+    left.get_disk() == right.get_disk() &&
+    left.has_revocable() == right.has_revocable()
+}
+
+fn is_empty(resource: &Resource) -> bool {
+    match resource.get_field_type() {
+        Value_Type::SCALAR => resource.get_scalar().get_value() <= EPSILON,
+        Value_Type::RANGES => resource.get_ranges().get_range().is_empty(),
+        Value_Type::SET => resource.get_set().get_item().is_empty(),
+        Value_Type::TEXT => false,
+    }
+}
+
+// Coalesces ranges and drops duplicate set items, so containment checks
+// can compare against a single stored range or item list.
+fn normalize(resource: &mut Resource) {
+    match resource.get_field_type() {
+        Value_Type::RANGES => {
+            let ranges = coalesce(to_pairs(resource.get_ranges()));
+            resource.set_ranges(from_pairs(ranges));
+        }
+        Value_Type::SET => {
+            let mut items: Vec<String> = vec![];
+            for item in resource.get_set().get_item() {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+            let mut set = Value_Set::new();
+            set.set_item(protobuf::RepeatedField::from_vec(items));
+            resource.set_set(set);
+        }
+        _ => (),
+    }
+}
+
+fn contains_resource(left: &Resource, right: &Resource) -> bool {
+    if !addable(left, right) {
+        return false;
+    }
+    if is_persistent_volume(right) {
+        return left == right;
+    }
+    match right.get_field_type() {
+        Value_Type::SCALAR => {
+            left.get_scalar().get_value() + EPSILON >=
+            right.get_scalar().get_value()
+        }
+        Value_Type::RANGES => {
+            let available = to_pairs(left.get_ranges());
+            to_pairs(right.get_ranges()).iter().all(|&(begin, end)| {
+                available.iter().any(|&(b, e)| b <= begin && end <= e)
+            })
+        }
+        Value_Type::SET => {
+            right.get_set()
+                 .get_item()
+                 .iter()
+                 .all(|i| left.get_set().get_item().contains(i))
+        }
+        Value_Type::TEXT => left == right,
+    }
+}
+
+fn merge(left: &mut Resource, right: &Resource) {
+    match right.get_field_type() {
+        Value_Type::SCALAR => {
+            let value = left.get_scalar().get_value() +
+                        right.get_scalar().get_value();
+            left.mut_scalar().set_value(value);
+        }
+        Value_Type::RANGES => {
+            let mut ranges = to_pairs(left.get_ranges());
+            ranges.extend(to_pairs(right.get_ranges()));
+            left.set_ranges(from_pairs(coalesce(ranges)));
+        }
+        Value_Type::SET => {
+            let mut items = left.get_set().get_item().to_vec();
+            for item in right.get_set().get_item() {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+            let mut set = Value_Set::new();
+            set.set_item(protobuf::RepeatedField::from_vec(items));
+            left.set_set(set);
+        }
+        Value_Type::TEXT => (),
+    }
+}
+
+fn remove(left: &mut Resource, right: &Resource) {
+    match right.get_field_type() {
+        Value_Type::SCALAR => {
+            let value = left.get_scalar().get_value() -
+                        right.get_scalar().get_value();
+            left.mut_scalar().set_value(if value < EPSILON {
+                0f64
+            } else {
+                value
+            });
+        }
+        Value_Type::RANGES => {
+            let ranges = subtract_ranges(to_pairs(left.get_ranges()),
+                                         &to_pairs(right.get_ranges()));
+            left.set_ranges(from_pairs(ranges));
+        }
+        Value_Type::SET => {
+            let items = left.get_set()
+                            .get_item()
+                            .iter()
+                            .filter(|i| !right.get_set().get_item().contains(i))
+                            .cloned()
+                            .collect();
+            let mut set = Value_Set::new();
+            set.set_item(protobuf::RepeatedField::from_vec(items));
+            left.set_set(set);
+        }
+        Value_Type::TEXT => (),
+    }
+}
+
+// Removes as much of a partially contained resource as possible.
+fn remove_overlap(resources: &mut Vec<Resource>, resource: &Resource) {
+    if is_persistent_volume(resource) {
+        return;
+    }
+    for existing in resources.iter_mut() {
+        if addable(existing, resource) {
+            remove(existing, resource);
+        }
+    }
+    resources.retain(|r| !is_empty(r));
+}
+
+fn to_pairs(ranges: &Value_Ranges) -> Vec<(u64, u64)> {
+    ranges.get_range()
+          .iter()
+          .map(|r| (r.get_begin(), r.get_end()))
+          .collect()
+}
+
+pub fn from_pairs(pairs: Vec<(u64, u64)>) -> Value_Ranges {
+    let ranges = pairs.into_iter()
+                      .map(|(begin, end)| {
+                          let mut range = Value_Range::new();
+                          range.set_begin(begin);
+                          range.set_end(end);
+                          range
+                      })
+                      .collect();
+    let mut value = Value_Ranges::new();
+    value.set_range(protobuf::RepeatedField::from_vec(ranges));
+    value
+}
+
+// Sorts inclusive ranges and merges the ones that overlap or touch.
+pub fn coalesce(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort();
+    let mut result: Vec<(u64, u64)> = vec![];
+    for (begin, end) in ranges {
+        if let Some(last) = result.last_mut() {
+            if begin <= last.1.saturating_add(1) {
+                if end > last.1 {
+                    last.1 = end;
+                }
+                continue;
+            }
+        }
+        result.push((begin, end));
+    }
+    result
+}
+
+// Removes every range in `remove` from `ranges`, splitting ranges where
+// needed.
+pub fn subtract_ranges(ranges: Vec<(u64, u64)>,
+                       remove: &[(u64, u64)])
+                       -> Vec<(u64, u64)> {
+    let mut result = coalesce(ranges);
+    for &(rb, re) in remove {
+        let mut next = vec![];
+        for (b, e) in result {
+            if re < b || rb > e {
+                next.push((b, e));
+                continue;
+            }
+            if b < rb {
+                next.push((b, rb - 1));
+            }
+            if e > re {
+                next.push((re + 1, e));
+            }
+        }
+        result = next;
+    }
+    result
+}
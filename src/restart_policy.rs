@@ -0,0 +1,226 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use offer_inventory;
+use task_registry;
+use proto::mesos::{TaskID, TaskState, TaskStatus, TaskStatus_Reason};
+use util;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestartPolicy {
+    // Restart whenever the task terminates, even when it finished.
+    Always,
+    // Restart tasks that failed, were lost or were killed, up to
+    // max_retries times.  Tasks rejected as invalid or unauthorized are
+    // not retried, as they would be rejected again.
+    OnFailure { max_retries: u32 },
+    Never,
+}
+
+impl RestartPolicy {
+    // Whether an instance that has already been restarted `restarts`
+    // times should be restarted after this terminal update.
+    pub fn should_restart(&self, status: &TaskStatus, restarts: u32) -> bool {
+        match *self {
+            RestartPolicy::Always => true,
+            RestartPolicy::OnFailure { max_retries } => {
+                if restarts >= max_retries {
+                    return false;
+                }
+                if status.has_reason() {
+                    match status.get_reason() {
+                        TaskStatus_Reason::REASON_TASK_INVALID |
+                        TaskStatus_Reason::REASON_TASK_UNAUTHORIZED => {
+                            return false
+                        }
+                        _ => (),
+                    }
+                }
+                status.get_state() != TaskState::TASK_FINISHED
+            }
+            RestartPolicy::Never => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstanceState {
+    // Waiting to be launched, once `ready` lists it.
+    Pending,
+    Launched,
+    // Terminated for good, by policy or because it was stopped.
+    Done,
+}
+
+// A logical instance of a service, which outlives the tasks launched
+// for it.
+#[derive(Clone, Debug)]
+pub struct Instance {
+    pub name: String,
+    pub policy: RestartPolicy,
+    pub state: InstanceState,
+    pub task_id: Option<TaskID>,
+    pub restarts: u32,
+    pub last_status: Option<TaskStatus>,
+    generation: i64,
+    stopping: bool,
+    not_before: Option<Instant>,
+}
+
+impl Instance {
+    // A fresh task id for the next launch, "<name>.<generation>.<restarts>".
+    // The generation keeps ids unique across scheduler restarts under the
+    // same framework id.
+    pub fn next_task_id(&self) -> TaskID {
+        util::task_id(&format!("{}.{}.{}",
+                               self.name,
+                               self.generation,
+                               self.restarts))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Restart {
+    // The instance will be ready for launch again after the delay.
+    After(Duration),
+    Done,
+}
+
+// Relaunches the tasks of logical instances according to their restart
+// policies, with exponential backoff between restarts of an instance.
+//
+// Call update from Scheduler::update.  On the offer path, launch the
+// instances listed by ready, using Instance::next_task_id, and report
+// each with launched.  Instances stopped through stop are not restarted
+// when their task terminates.
+pub struct RestartManager {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    // Part of every task id, set to when the manager was created.
+    // Instances take the value current when they are added.
+    pub generation: i64,
+    instances: HashMap<String, Instance>,
+    decide: Option<Box<FnMut(&Instance, &TaskStatus) -> bool>>,
+}
+
+impl RestartManager {
+    pub fn new() -> RestartManager {
+        RestartManager {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            generation: offer_inventory::now_nanos(),
+            instances: HashMap::new(),
+            decide: None,
+        }
+    }
+
+    // Replaces the policies' decision, e.g. to look at the update's
+    // reason or message.
+    pub fn decide_with<F>(&mut self, decide: F)
+        where F: FnMut(&Instance, &TaskStatus) -> bool + 'static
+    {
+        self.decide = Some(Box::new(decide));
+    }
+
+    pub fn add(&mut self, name: &str, policy: RestartPolicy) {
+        self.instances.insert(name.to_string(),
+                              Instance {
+                                  name: name.to_string(),
+                                  policy: policy,
+                                  state: InstanceState::Pending,
+                                  task_id: None,
+                                  restarts: 0,
+                                  last_status: None,
+                                  generation: self.generation,
+                                  stopping: false,
+                                  not_before: None,
+                              });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Instance> {
+        self.instances.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Instance> {
+        self.instances.remove(name)
+    }
+
+    pub fn instances(&self) -> Vec<&Instance> {
+        self.instances.values().collect()
+    }
+
+    pub fn launched(&mut self, name: &str, task_id: &TaskID) {
+        if let Some(instance) = self.instances.get_mut(name) {
+            instance.state = InstanceState::Launched;
+            instance.task_id = Some(task_id.clone());
+            instance.not_before = None;
+        }
+    }
+
+    // Pending instances whose backoff has elapsed.
+    pub fn ready(&self) -> Vec<&Instance> {
+        let now = Instant::now();
+        let mut ready: Vec<&Instance> =
+            self.instances
+                .values()
+                .filter(|i| {
+                    i.state == InstanceState::Pending &&
+                    i.not_before.map_or(true, |at| now >= at)
+                })
+                .collect();
+        ready.sort_by(|a, b| a.name.cmp(&b.name));
+        ready
+    }
+
+    // Marks the instance so that its task is not restarted once it
+    // terminates.  Killing the task is up to the caller.
+    pub fn stop(&mut self, name: &str) {
+        if let Some(instance) = self.instances.get_mut(name) {
+            instance.stopping = true;
+            if instance.state == InstanceState::Pending {
+                instance.state = InstanceState::Done;
+            }
+        }
+    }
+
+    // Returns the outcome when the update terminated an instance's
+    // current task.
+    pub fn update(&mut self, status: &TaskStatus) -> Option<Restart> {
+        if !task_registry::is_terminal(&status.get_state()) {
+            return None;
+        }
+        let task_id = status.get_task_id().get_value();
+        let instance = match self.instances.values_mut().find(|i| {
+            i.state == InstanceState::Launched &&
+            i.task_id.as_ref().map_or(false, |t| t.get_value() == task_id)
+        }) {
+            Some(instance) => instance,
+            None => return None,
+        };
+        instance.last_status = Some(status.clone());
+
+        let restart = if instance.stopping {
+            false
+        } else {
+            match self.decide {
+                Some(ref mut decide) => decide(instance, status),
+                None => {
+                    instance.policy.should_restart(status, instance.restarts)
+                }
+            }
+        };
+        if !restart {
+            instance.state = InstanceState::Done;
+            return Some(Restart::Done);
+        }
+
+        let exponent = cmp::min(instance.restarts, 16);
+        let delay = cmp::min(self.initial_backoff * 2u32.pow(exponent),
+                             self.max_backoff);
+        instance.restarts += 1;
+        instance.state = InstanceState::Pending;
+        instance.not_before = Some(Instant::now() + delay);
+        Some(Restart::After(delay))
+    }
+}
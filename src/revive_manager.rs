@@ -0,0 +1,76 @@
+use scheduler_client::SchedulerClient;
+use util;
+
+// Keeps the master's offer flow in line with how much work is pending:
+// SUPPRESS once there is nothing left to launch, so idle frameworks do
+// not tie up the allocator, and REVIVE as soon as work appears again,
+// which also clears the refuse filters of earlier declines.
+//
+// Call set_pending whenever the amount of pending work changes, e.g.
+// with TaskQueue::len, subscribed from Scheduler::subscribed and
+// disconnected from Scheduler::disconnected.  The master does not keep a
+// framework's suppression across subscriptions, so it is re-applied on
+// every subscription.  A call the master did not accept leaves the state
+// as it was, so the next set_pending sends it again.
+pub struct ReviveManager {
+    pending: usize,
+    suppressed: bool,
+    subscribed: bool,
+}
+
+impl ReviveManager {
+    pub fn new() -> ReviveManager {
+        ReviveManager {
+            pending: 0,
+            suppressed: false,
+            subscribed: false,
+        }
+    }
+
+    pub fn subscribed(&mut self, client: &SchedulerClient) {
+        self.subscribed = true;
+        self.suppressed = false;
+        if self.pending == 0 {
+            self.suppressed = util::is_success(&client.suppress());
+        }
+    }
+
+    pub fn disconnected(&mut self) {
+        self.subscribed = false;
+    }
+
+    // Returns true if a SUPPRESS or REVIVE was sent.  Nothing is sent
+    // while disconnected; the state is applied on the next subscription.
+    pub fn set_pending(&mut self,
+                       client: &SchedulerClient,
+                       pending: usize)
+                       -> bool {
+        let had_work = self.pending > 0;
+        self.pending = pending;
+        if !self.subscribed {
+            return false;
+        }
+
+        if pending == 0 && !self.suppressed {
+            if util::is_success(&client.suppress()) {
+                self.suppressed = true;
+            }
+            true
+        } else if pending > 0 && (self.suppressed || !had_work) {
+            if util::is_success(&client.revive()) {
+                self.suppressed = false;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_suppressed(&self) -> bool {
+        self.suppressed
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+}
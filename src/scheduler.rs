@@ -35,5 +35,10 @@ pub struct SchedulerConf {
     pub name: String,
     pub framework_timeout: f64,
     pub implicit_acknowledgements: bool,
+    // When true, each offer batch is split so that every call to
+    // Scheduler::offers and Scheduler::inverse_offers only receives
+    // offers for a single agent.  When false, the whole batch is
+    // delivered in one call.
+    pub offers_per_agent: bool,
     pub framework_id: Option<FrameworkID>,
 }
@@ -12,14 +12,17 @@ use proto::scheduler::{Call, Call_Accept, Call_Acknowledge, Call_Decline,
                        Call_Kill, Call_Message, Call_Reconcile,
                        Call_Reconcile_Task, Call_Request, Call_Shutdown,
                        Call_Subscribe, Call_Type};
-use proto::mesos::{AgentID, ExecutorID, Filters, FrameworkID, FrameworkInfo,
-                   OfferID, Operation, Request, TaskID, TaskInfo};
+use scheduler_middleware::CallObserver;
+use proto::mesos::{AgentID, ExecutorID, ExecutorInfo, Filters, FrameworkID,
+                   FrameworkInfo, OfferID, Operation, Request, Resource,
+                   TaskID, TaskInfo};
 use util;
 
 #[derive(Clone)]
 pub struct SchedulerClient {
     pub url: String,
     pub framework_id: Arc<Mutex<Option<FrameworkID>>>,
+    pub observers: Arc<Mutex<Vec<Arc<CallObserver>>>>,
 }
 
 impl SchedulerClient {
@@ -29,9 +32,16 @@ impl SchedulerClient {
         SchedulerClient {
             url: url + "/api/v1/scheduler",
             framework_id: Arc::new(Mutex::new(framework_id)),
+            observers: Arc::new(Mutex::new(vec![])),
         }
     }
 
+    // Observers are shared by every clone of this client, and see each
+    // outgoing Call right before it is posted to the master.
+    pub fn add_observer(&self, observer: Arc<CallObserver>) {
+        self.observers.lock().unwrap().push(observer);
+    }
+
     pub fn get_framework_id(&self) -> Option<FrameworkID> {
         let id = self.framework_id.lock().unwrap().clone();
         id
@@ -72,6 +82,91 @@ impl SchedulerClient {
         self.accept(offer_ids, vec![operation], filters)
     }
 
+    // Launches tasks on a shared custom executor.  The executor is
+    // started with the first batch and reused by later launches with the
+    // same ExecutorInfo; only include its resources in the offer
+    // accounting while executor_builder::is_running is false.
+    pub fn launch_with_executor(&self,
+                                offer_ids: Vec<OfferID>,
+                                mut executor: ExecutorInfo,
+                                tasks: Vec<TaskInfo>,
+                                filters: Option<Filters>)
+                                -> hyper::Result<Response> {
+        if !executor.has_framework_id() {
+            if let Some(fwid) = self.get_framework_id() {
+                executor.set_framework_id(fwid);
+            }
+        }
+
+        let tasks = tasks.into_iter()
+                         .map(|mut task| {
+                             task.clear_command();
+                             task.set_executor(executor.clone());
+                             task
+                         })
+                         .collect();
+        self.launch(offer_ids, tasks, filters)
+    }
+
+    pub fn reserve(&self,
+                   offer_ids: Vec<OfferID>,
+                   resources: Vec<Resource>,
+                   filters: Option<Filters>)
+                   -> hyper::Result<Response> {
+        let operation = util::reserve_operation(resources);
+        self.accept(offer_ids, vec![operation], filters)
+    }
+
+    pub fn unreserve(&self,
+                     offer_ids: Vec<OfferID>,
+                     resources: Vec<Resource>,
+                     filters: Option<Filters>)
+                     -> hyper::Result<Response> {
+        let operation = util::unreserve_operation(resources);
+        self.accept(offer_ids, vec![operation], filters)
+    }
+
+    pub fn create_volumes(&self,
+                          offer_ids: Vec<OfferID>,
+                          volumes: Vec<Resource>,
+                          filters: Option<Filters>)
+                          -> hyper::Result<Response> {
+        let operation = util::create_operation(volumes);
+        self.accept(offer_ids, vec![operation], filters)
+    }
+
+    pub fn destroy_volumes(&self,
+                           offer_ids: Vec<OfferID>,
+                           volumes: Vec<Resource>,
+                           filters: Option<Filters>)
+                           -> hyper::Result<Response> {
+        let operation = util::destroy_operation(volumes);
+        self.accept(offer_ids, vec![operation], filters)
+    }
+
+    // Reserves resources, creates persistent volumes out of them and
+    // launches tasks in a single ACCEPT.  Tasks that use a volume must
+    // list it among their resources.
+    pub fn reserve_and_launch(&self,
+                              offer_ids: Vec<OfferID>,
+                              reserve: Vec<Resource>,
+                              volumes: Vec<Resource>,
+                              tasks: Vec<TaskInfo>,
+                              filters: Option<Filters>)
+                              -> hyper::Result<Response> {
+        let mut operations = vec![];
+        if reserve.len() > 0 {
+            operations.push(util::reserve_operation(reserve));
+        }
+        if volumes.len() > 0 {
+            operations.push(util::create_operation(volumes));
+        }
+        if tasks.len() > 0 {
+            operations.push(util::launch_operation(tasks));
+        }
+        self.accept(offer_ids, operations, filters)
+    }
+
     pub fn accept(&self,
                   offer_ids: Vec<OfferID>,
                   operations: Vec<Operation>,
@@ -233,6 +328,13 @@ impl SchedulerClient {
             _ => (),
         }
 
+        // Release the lock before notifying, so observers may use the
+        // client themselves.
+        let observers = self.observers.lock().unwrap().clone();
+        for observer in observers {
+            observer.call(call);
+        }
+
         let client = Client::new();
 
         let data = &*call.write_to_bytes().unwrap();
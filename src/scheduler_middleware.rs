@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::mem;
+use std::sync::Mutex;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+use protobuf;
+
+use scheduler_client::SchedulerClient;
+use proto::mesos::OfferID;
+use proto::scheduler::*;
+use {SchedulerConf, SchedulerRouter};
+
+// A layer that sees every event before it reaches the wrapped router.
+// Returning None swallows the event, otherwise the (possibly modified)
+// event is handed to the next layer.
+pub trait SchedulerMiddleware: Send {
+    fn event(&mut self,
+             client: &SchedulerClient,
+             event: Event)
+             -> Option<Event>;
+}
+
+// Observes every Call sent through a SchedulerClient.  Register with
+// SchedulerClient::add_observer.
+pub trait CallObserver: Send + Sync {
+    fn call(&self, call: &Call);
+}
+
+// Wraps another router, passing events through each middleware layer in
+// order.  Layers run on their own thread, so they must be Send.
+// Connection errors bypass the layers so the wrapped router still sees
+// disconnections.
+pub struct MiddlewareRouter<'a> {
+    pub router: &'a mut SchedulerRouter,
+    pub middleware: Vec<Box<SchedulerMiddleware>>,
+}
+
+impl<'a> MiddlewareRouter<'a> {
+    pub fn new(router: &'a mut SchedulerRouter) -> MiddlewareRouter<'a> {
+        MiddlewareRouter {
+            router: router,
+            middleware: vec![],
+        }
+    }
+
+    pub fn add(&mut self, middleware: Box<SchedulerMiddleware>) {
+        self.middleware.push(middleware);
+    }
+}
+
+impl<'a> SchedulerRouter for MiddlewareRouter<'a> {
+    fn run(&mut self,
+           rx: Receiver<io::Result<Event>>,
+           client: SchedulerClient,
+           conf: SchedulerConf) {
+        let mut middleware = mem::replace(&mut self.middleware, vec![]);
+        let (tx, inner_rx) = channel();
+        let local_client = client.clone();
+
+        let handle = thread::spawn(move || {
+            for e in rx {
+                let routed = match e {
+                    Err(e) => Some(Err(e)),
+                    Ok(event) => apply(&mut middleware, &local_client, event)
+                                     .map(Ok),
+                };
+                if let Some(e) = routed {
+                    if tx.send(e).is_err() {
+                        break;
+                    }
+                }
+            }
+            middleware
+        });
+
+        self.router.run(inner_rx, client, conf);
+
+        // Hand the layers back so their state can be inspected after
+        // the stream ends.
+        if let Ok(middleware) = handle.join() {
+            self.middleware = middleware;
+        }
+    }
+}
+
+fn apply(middleware: &mut Vec<Box<SchedulerMiddleware>>,
+         client: &SchedulerClient,
+         event: Event)
+         -> Option<Event> {
+    let mut event = event;
+    for layer in middleware.iter_mut() {
+        match layer.event(client, event) {
+            Some(e) => event = e,
+            None => return None,
+        }
+    }
+    Some(event)
+}
+
+// Writes a line for every event type it sees.  Write errors are
+// ignored so logging never gets in the way of the scheduler.
+pub struct EventLogger {
+    out: Box<Write + Send>,
+}
+
+impl EventLogger {
+    pub fn new(out: Box<Write + Send>) -> EventLogger {
+        EventLogger { out: out }
+    }
+}
+
+impl SchedulerMiddleware for EventLogger {
+    fn event(&mut self,
+             _: &SchedulerClient,
+             event: Event)
+             -> Option<Event> {
+        let _ = writeln!(self.out,
+                         "received event {:?}",
+                         event.get_field_type());
+        Some(event)
+    }
+}
+
+// Writes a line for every call type sent to the master.
+pub struct CallLogger {
+    out: Mutex<Box<Write + Send>>,
+}
+
+impl CallLogger {
+    pub fn new(out: Box<Write + Send>) -> CallLogger {
+        CallLogger { out: Mutex::new(out) }
+    }
+}
+
+impl CallObserver for CallLogger {
+    fn call(&self, call: &Call) {
+        let mut out = self.out.lock().unwrap();
+        let _ = writeln!(out, "sending call {:?}", call.get_field_type());
+    }
+}
+
+// Declines offers from blacklisted agents before the scheduler ever sees
+// them.  Their inverse offers are dropped unanswered, since declining
+// one would tell the master the framework won't vacate the agent.
+// Offer events left empty are swallowed.
+pub struct AgentBlacklist {
+    pub agents: HashSet<String>,
+}
+
+impl AgentBlacklist {
+    pub fn new(agents: Vec<String>) -> AgentBlacklist {
+        AgentBlacklist { agents: agents.into_iter().collect() }
+    }
+}
+
+impl SchedulerMiddleware for AgentBlacklist {
+    fn event(&mut self,
+             client: &SchedulerClient,
+             mut event: Event)
+             -> Option<Event> {
+        if event.get_field_type() != Event_Type::OFFERS {
+            return Some(event);
+        }
+
+        let mut declined: Vec<OfferID> = vec![];
+        {
+            let offers = event.mut_offers();
+
+            let (blocked, allowed): (Vec<_>, Vec<_>) =
+                offers.take_offers()
+                      .into_vec()
+                      .into_iter()
+                      .partition(|o| {
+                          self.agents.contains(o.get_agent_id().get_value())
+                      });
+            declined.extend(blocked.iter().map(|o| o.get_id().clone()));
+            offers.set_offers(protobuf::RepeatedField::from_vec(allowed));
+
+            let allowed: Vec<_> =
+                offers.take_inverse_offers()
+                      .into_vec()
+                      .into_iter()
+                      .filter(|o| {
+                          !self.agents.contains(o.get_agent_id().get_value())
+                      })
+                      .collect();
+            offers.set_inverse_offers(
+                protobuf::RepeatedField::from_vec(allowed));
+        }
+
+        if declined.len() > 0 {
+            client.decline(declined, None);
+        }
+
+        if event.get_offers().get_offers().len() == 0 &&
+           event.get_offers().get_inverse_offers().len() == 0 {
+            None
+        } else {
+            Some(event)
+        }
+    }
+}
@@ -3,11 +3,9 @@ use std::sync::{Arc, Mutex};
 use std::sync::mpsc::{Receiver, channel};
 use std::thread;
 
-use itertools::Itertools;
-
 use scheduler_client::SchedulerClient;
 use recordio::RecordIOCodec;
-use proto::mesos::{FrameworkID, Offer};
+use proto::mesos::{AgentID, FrameworkID, Offer};
 use proto::scheduler::*;
 use {Scheduler, SchedulerConf, util};
 
@@ -80,22 +78,34 @@ impl <'a> SchedulerRouter for ProtobufCallbackRouter<'a> {
                 Event_Type::OFFERS => {
                     let offers = event.get_offers();
 
-                    // Split offers per-agent to save users the time of
-                    // doing so.
-                    for (_, offers) in offers.get_offers()
-                                             .iter()
-                                             .group_by(|o| o.get_agent_id()) {
-                        self.scheduler.offers(&client, offers.to_vec());
-                    }
-                    for (_, inverse_offers) in offers.get_inverse_offers()
-                                                     .iter()
-                                                     .group_by(|o| {
-                                                         o.get_agent_id()
-                                                     }) {
-                        self.scheduler
-                            .inverse_offers(&client, inverse_offers.to_vec());
+                    if self.conf.offers_per_agent {
+                        // Split offers per-agent to save users the time of
+                        // doing so.
+                        for offers in group_by_agent(offers.get_offers(),
+                                                     |o| o.get_agent_id()) {
+                            self.scheduler.offers(&client, offers);
+                        }
+                        for inverse_offers in
+                            group_by_agent(offers.get_inverse_offers(),
+                                           |o| o.get_agent_id()) {
+                            self.scheduler
+                                .inverse_offers(&client, inverse_offers);
+                        }
+                    } else {
+                        if offers.get_offers().len() > 0 {
+                            self.scheduler.offers(&client,
+                                                  offers.get_offers()
+                                                        .iter()
+                                                        .collect());
+                        }
+                        if offers.get_inverse_offers().len() > 0 {
+                            self.scheduler
+                                .inverse_offers(&client,
+                                                offers.get_inverse_offers()
+                                                      .iter()
+                                                      .collect());
+                        }
                     }
-
                 }
                 Event_Type::RESCIND =>
                     self.scheduler
@@ -103,7 +113,10 @@ impl <'a> SchedulerRouter for ProtobufCallbackRouter<'a> {
                 Event_Type::UPDATE => {
                     let status = event.get_update().get_status();
                     self.scheduler.update(&client, status);
-                    if self.conf.implicit_acknowledgements {
+                    // Updates without a uuid, such as reconciliation
+                    // responses, must not be acknowledged.
+                    if self.conf.implicit_acknowledgements &&
+                       status.has_uuid() {
                         client.acknowledge(status.get_agent_id().clone(),
                                            status.get_task_id().clone(),
                                            status.get_uuid().to_vec());
@@ -145,3 +158,24 @@ impl <'a> SchedulerRouter for ProtobufCallbackRouter<'a> {
         }
     }
 }
+
+// Groups items by agent, preserving the order in which each agent was
+// first seen.  Unlike itertools' group_by, non-adjacent items for the same
+// agent end up in the same group.
+fn group_by_agent<'a, T, F>(items: &'a [T], agent_id: F) -> Vec<Vec<&'a T>>
+    where F: Fn(&'a T) -> &'a AgentID
+{
+    let mut agents: Vec<&'a str> = vec![];
+    let mut groups: Vec<Vec<&'a T>> = vec![];
+    for item in items {
+        let id = agent_id(item).get_value();
+        match agents.iter().position(|a| *a == id) {
+            Some(idx) => groups[idx].push(item),
+            None => {
+                agents.push(id);
+                groups.push(vec![item]);
+            }
+        }
+    }
+    groups
+}
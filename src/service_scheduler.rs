@@ -0,0 +1,558 @@
+use std::cmp;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use constraints::{self, Constraint, PlacedTask};
+use decline_policy::{DeclinePolicy, RefuseStrategy};
+use kill_manager::KillManager;
+use offer_inventory;
+use reconciler::Reconciler;
+use resources::Resources;
+use revive_manager::ReviveManager;
+use scheduler::Scheduler;
+use scheduler_client::SchedulerClient;
+use task_info_builder::TaskInfoBuilder;
+use task_registry::{self, TaskRegistry};
+use proto::*;
+use util;
+
+// What to run, and how many copies of it.
+#[derive(Clone, Debug)]
+pub struct AppDefinition {
+    pub name: String,
+    pub command: CommandInfo,
+    pub container: Option<ContainerInfo>,
+    pub resources: Vec<Resource>,
+    pub instances: usize,
+    pub constraints: Vec<Constraint>,
+    pub health_check: Option<HealthCheck>,
+}
+
+impl AppDefinition {
+    pub fn new(name: &str,
+               command: CommandInfo,
+               resources: Vec<Resource>,
+               instances: usize)
+               -> AppDefinition {
+        AppDefinition {
+            name: name.to_string(),
+            command: command,
+            container: None,
+            resources: resources,
+            instances: instances,
+            constraints: vec![],
+            health_check: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("apps require a name".to_string());
+        }
+        self.task_info(&self.name,
+                       &util::task_id(&self.name),
+                       &AgentID::new(),
+                       0)
+            .map(|_| ())
+    }
+
+    fn task_info(&self,
+                 name: &str,
+                 task_id: &TaskID,
+                 agent_id: &AgentID,
+                 version: u64)
+                 -> Result<TaskInfo, String> {
+        let mut builder = TaskInfoBuilder::new()
+                              .name(name)
+                              .task_id(task_id)
+                              .agent_id(agent_id)
+                              .resources(self.resources.clone())
+                              .command(self.command.clone())
+                              .label("app", &self.name)
+                              .label("version", &version.to_string());
+        if let Some(ref container) = self.container {
+            builder = builder.container(container.clone());
+        }
+        if let Some(ref health_check) = self.health_check {
+            builder = builder.health_check(health_check.clone());
+        }
+        builder.build()
+    }
+}
+
+struct Control {
+    instances: usize,
+    app: Option<AppDefinition>,
+}
+
+// Changes a running ServiceScheduler from another thread.  Changes are
+// picked up on the scheduler's next event, at the latest on the next
+// heartbeat.
+#[derive(Clone)]
+pub struct ServiceHandle {
+    control: Arc<Mutex<Control>>,
+}
+
+impl ServiceHandle {
+    pub fn scale(&self, instances: usize) {
+        self.control.lock().unwrap().instances = instances;
+    }
+
+    pub fn instances(&self) -> usize {
+        self.control.lock().unwrap().instances
+    }
+
+    // Replaces the definition, including its instance count.  Running
+    // instances are restarted onto it one at a time.
+    pub fn update(&self, app: AppDefinition) -> Result<(), String> {
+        try!(app.validate());
+        let mut control = self.control.lock().unwrap();
+        control.instances = app.instances;
+        control.app = Some(app);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstanceState {
+    // Waiting for an offer.
+    Pending,
+    Staging,
+    Running,
+    Killing,
+}
+
+#[derive(Clone, Debug)]
+pub struct ServiceInstance {
+    pub index: usize,
+    pub name: String,
+    pub state: InstanceState,
+    // The definition version the current task was launched with.
+    pub version: u64,
+    pub task_id: Option<TaskID>,
+    pub agent_id: Option<AgentID>,
+    // Failures since the instance last reached TASK_RUNNING.
+    pub failures: u32,
+    placed: Option<PlacedTask>,
+    not_before: Option<Instant>,
+    scaled_down: bool,
+}
+
+// A Scheduler that keeps `instances` copies of an app running.
+//
+// Instances are named "<app>-<index>".  Failed tasks are relaunched with
+// exponential backoff, and constraints are checked against where the
+// other instances run.  When the definition changes, instances are
+// killed and relaunched onto it one at a time, each only once every
+// instance is running, and healthy if the app has a health check.
+//
+// Status updates are expected to be acknowledged implicitly.
+pub struct ServiceScheduler {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub decline_policy: DeclinePolicy,
+    pub kills: KillManager,
+    app: AppDefinition,
+    version: u64,
+    handle: ServiceHandle,
+    instances: BTreeMap<usize, ServiceInstance>,
+    registry: TaskRegistry,
+    reconciler: Reconciler,
+    revive: ReviveManager,
+    generation: i64,
+    launches: u64,
+}
+
+impl ServiceScheduler {
+    pub fn new(app: AppDefinition) -> Result<ServiceScheduler, String> {
+        try!(app.validate());
+        let handle = ServiceHandle {
+            control: Arc::new(Mutex::new(Control {
+                instances: app.instances,
+                app: None,
+            })),
+        };
+        Ok(ServiceScheduler {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            decline_policy: DeclinePolicy::new(RefuseStrategy::Exponential {
+                initial: 5f64,
+                max: 120f64,
+            }),
+            kills: KillManager::new(),
+            app: app,
+            version: 0,
+            handle: handle,
+            instances: BTreeMap::new(),
+            registry: TaskRegistry::new(),
+            reconciler: Reconciler::new(),
+            revive: ReviveManager::new(),
+            generation: offer_inventory::now_nanos(),
+            launches: 0,
+        })
+    }
+
+    pub fn handle(&self) -> ServiceHandle {
+        self.handle.clone()
+    }
+
+    pub fn app(&self) -> &AppDefinition {
+        &self.app
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn instances(&self) -> Vec<&ServiceInstance> {
+        self.instances.values().collect()
+    }
+
+    pub fn registry(&self) -> &TaskRegistry {
+        &self.registry
+    }
+
+    // Applies changes made through the handle.
+    fn sync(&mut self, client: &SchedulerClient) {
+        let (target, app) = {
+            let mut control = self.handle.control.lock().unwrap();
+            (control.instances, control.app.take())
+        };
+        if let Some(app) = app {
+            self.app = app;
+            self.version += 1;
+        }
+
+        for index in 0..target {
+            let version = self.version;
+            let name = format!("{}-{}", self.app.name, index);
+            let instance = self.instances
+                               .entry(index)
+                               .or_insert(ServiceInstance {
+                                   index: index,
+                                   name: name,
+                                   state: InstanceState::Pending,
+                                   version: version,
+                                   task_id: None,
+                                   agent_id: None,
+                                   failures: 0,
+                                   placed: None,
+                                   not_before: None,
+                                   scaled_down: false,
+                               });
+            instance.scaled_down = false;
+        }
+
+        let excess: Vec<usize> = self.instances
+                                     .keys()
+                                     .filter(|&&index| index >= target)
+                                     .cloned()
+                                     .collect();
+        for index in excess {
+            self.kill(client, index, true);
+        }
+    }
+
+    fn kill(&mut self, client: &SchedulerClient, index: usize, remove: bool) {
+        let task = match self.instances.get_mut(&index) {
+            Some(instance) => {
+                instance.scaled_down = remove;
+                if instance.state == InstanceState::Killing {
+                    return;
+                }
+                match instance.task_id.clone() {
+                    Some(task_id) => {
+                        instance.state = InstanceState::Killing;
+                        Some((task_id, instance.agent_id.clone()))
+                    }
+                    None => None,
+                }
+            }
+            None => return,
+        };
+        match task {
+            Some((task_id, agent_id)) => {
+                let executor_id = self.registry
+                                      .get(&task_id)
+                                      .and_then(|r| r.executor_id.as_ref());
+                self.kills.kill(client,
+                                &task_id,
+                                agent_id.as_ref(),
+                                executor_id)
+            }
+            None if remove => {
+                self.instances.remove(&index);
+            }
+            None => (),
+        }
+    }
+
+    // Instances whose kill KillManager gave up on are relaunched, or
+    // forgotten if they were scaled down.
+    fn abandon_kills(&mut self) {
+        for kill in self.kills.take_failed() {
+            let index = match self.index_of(&kill.task_id) {
+                Some(index) => index,
+                None => continue,
+            };
+            self.registry.remove(&kill.task_id);
+            let scaled_down = {
+                let instance = self.instances.get_mut(&index).unwrap();
+                unplace(instance);
+                instance.scaled_down
+            };
+            if scaled_down {
+                self.instances.remove(&index);
+            }
+        }
+    }
+
+    // Kills the next instance still on an old definition, once every
+    // instance is up.
+    fn roll(&mut self, client: &SchedulerClient) {
+        let settled = self.instances.values().all(|i| {
+            i.state == InstanceState::Running &&
+            (self.app.health_check.is_none() ||
+             i.task_id
+              .as_ref()
+              .and_then(|t| self.registry.get(t))
+              .map_or(false, |r| r.healthy == Some(true)))
+        });
+        if !settled {
+            return;
+        }
+        let version = self.version;
+        let stale = self.instances
+                        .values()
+                        .find(|i| i.version != version)
+                        .map(|i| i.index);
+        if let Some(index) = stale {
+            self.kill(client, index, false);
+        }
+    }
+
+    fn pending(&self) -> usize {
+        self.instances
+            .values()
+            .filter(|i| i.state == InstanceState::Pending)
+            .count()
+    }
+
+    fn index_of(&self, task_id: &TaskID) -> Option<usize> {
+        self.instances
+            .values()
+            .find(|i| {
+                i.task_id
+                 .as_ref()
+                 .map_or(false, |t| t.get_value() == task_id.get_value())
+            })
+            .map(|i| i.index)
+    }
+
+    fn backoff(&self, failures: u32) -> Duration {
+        let exponent = cmp::min(failures.saturating_sub(1), 16);
+        cmp::min(self.initial_backoff * 2u32.pow(exponent), self.max_backoff)
+    }
+}
+
+impl Scheduler for ServiceScheduler {
+    fn subscribed(&mut self,
+                  client: &SchedulerClient,
+                  _: &FrameworkID,
+                  _: Option<f64>) {
+        self.sync(client);
+        self.reconciler.subscribed_with_registry(client, &self.registry);
+        let pending = self.pending();
+        self.revive.set_pending(client, pending);
+        self.revive.subscribed(client);
+    }
+
+    fn offers(&mut self, client: &SchedulerClient, offers: Vec<&Offer>) {
+        self.sync(client);
+        let now = Instant::now();
+        let mut ready: Vec<usize> =
+            self.instances
+                .values()
+                .filter(|i| {
+                    i.state == InstanceState::Pending &&
+                    i.not_before.map_or(true, |at| now >= at)
+                })
+                .map(|i| i.index)
+                .collect();
+        let mut placed: Vec<PlacedTask> = self.instances
+                                              .values()
+                                              .filter_map(|i| {
+                                                  i.placed.clone()
+                                              })
+                                              .collect();
+        let required = Resources::from_vec(self.app.resources.clone());
+
+        let mut declined = vec![];
+        let mut unused = vec![];
+        for offer in offers {
+            let mut remaining = Resources::from_offer(offer);
+            let mut tasks = vec![];
+            while ready.len() > 0 && remaining.contains(&required) {
+                let fits = {
+                    let running: Vec<&PlacedTask> = placed.iter().collect();
+                    constraints::satisfies_all(&self.app.constraints,
+                                               offer,
+                                               &running)
+                };
+                if !fits {
+                    break;
+                }
+
+                let index = ready.remove(0);
+                self.launches += 1;
+                let instance = self.instances.get_mut(&index).unwrap();
+                let task_id = util::task_id(&format!("{}.{}.{}",
+                                                     instance.name,
+                                                     self.generation,
+                                                     self.launches));
+                // The definition was validated when it was set.
+                let task = self.app
+                               .task_info(&instance.name,
+                                          &task_id,
+                                          offer.get_agent_id(),
+                                          self.version)
+                               .unwrap();
+                let placement = PlacedTask::from_offer(&task_id, offer);
+                instance.state = InstanceState::Staging;
+                instance.version = self.version;
+                instance.task_id = Some(task_id);
+                instance.agent_id = Some(offer.get_agent_id().clone());
+                instance.placed = Some(placement.clone());
+                instance.not_before = None;
+                placed.push(placement);
+
+                remaining = remaining - required.clone();
+                self.registry.launched(&task);
+                tasks.push(task);
+            }
+
+            if tasks.is_empty() {
+                declined.push(offer);
+                continue;
+            }
+            let task_ids: Vec<TaskID> = tasks.iter()
+                                             .map(|t| t.get_task_id().clone())
+                                             .collect();
+            let res = client.launch(vec![offer.get_id().clone()], tasks, None);
+            if util::is_success(&res) {
+                self.decline_policy.accepted(offer.get_agent_id());
+                continue;
+            }
+            // The master never saw these tasks, so no update will come
+            // for them.  Hand the offer back and wait for the next ones.
+            unused.push(offer.get_id().clone());
+            for task_id in &task_ids {
+                self.registry.remove(task_id);
+                placed.retain(|p| p.task_id.get_value() != task_id.get_value());
+                if let Some(index) = self.index_of(task_id) {
+                    unplace(self.instances.get_mut(&index).unwrap());
+                }
+            }
+        }
+
+        if declined.len() > 0 {
+            self.decline_policy.decline(client, declined, ready.len() > 0);
+        }
+        // These offers fit, so they are not counted against their agents.
+        if unused.len() > 0 {
+            client.decline(unused, None);
+        }
+        let pending = self.pending();
+        self.revive.set_pending(client, pending);
+    }
+
+    fn inverse_offers(&mut self,
+                      _: &SchedulerClient,
+                      _: Vec<&InverseOffer>) {
+    }
+
+    fn rescind(&mut self, _: &SchedulerClient, _: &OfferID) {}
+
+    fn update(&mut self, client: &SchedulerClient, status: &TaskStatus) {
+        self.sync(client);
+        self.reconciler.update(client, status);
+        self.kills.update(status);
+
+        if let Some(index) = self.index_of(status.get_task_id()) {
+            let _ = self.registry.update(status);
+            let state = status.get_state();
+            let failures = self.instances[&index].failures + 1;
+            let backoff = self.backoff(failures);
+            let version = self.version;
+
+            let scaled_down = {
+                let instance = self.instances.get_mut(&index).unwrap();
+                // Health changes and reconciliation answers also arrive as
+                // TASK_RUNNING, including while the task is being killed.
+                if state == TaskState::TASK_RUNNING &&
+                   instance.state != InstanceState::Killing {
+                    instance.state = InstanceState::Running;
+                    instance.failures = 0;
+                } else if task_registry::is_terminal(&state) {
+                    // Kills we asked for are not failures.
+                    if instance.state != InstanceState::Killing {
+                        instance.failures = failures;
+                        instance.not_before = Some(Instant::now() + backoff);
+                    }
+                    unplace(instance);
+                    instance.version = version;
+                }
+                instance.scaled_down
+            };
+            if task_registry::is_terminal(&state) {
+                self.registry.remove(status.get_task_id());
+                if scaled_down {
+                    self.instances.remove(&index);
+                }
+            }
+        }
+
+        self.roll(client);
+        let pending = self.pending();
+        self.revive.set_pending(client, pending);
+    }
+
+    fn message(&mut self,
+               _: &SchedulerClient,
+               _: &AgentID,
+               _: &ExecutorID,
+               _: Vec<u8>) {
+    }
+
+    fn failure(&mut self,
+               _: &SchedulerClient,
+               _: Option<&AgentID>,
+               _: Option<&ExecutorID>,
+               _: Option<i32>) {
+    }
+
+    fn error(&mut self, _: &SchedulerClient, _: String) {}
+
+    fn heartbeat(&mut self, client: &SchedulerClient) {
+        self.sync(client);
+        self.kills.tick(client);
+        self.abandon_kills();
+        self.reconciler.tick(client);
+        self.roll(client);
+        let pending = self.pending();
+        self.revive.set_pending(client, pending);
+    }
+
+    fn disconnected(&mut self) {
+        self.revive.disconnected();
+    }
+}
+
+// Returns an instance to waiting for an offer.
+fn unplace(instance: &mut ServiceInstance) {
+    instance.state = InstanceState::Pending;
+    instance.task_id = None;
+    instance.agent_id = None;
+    instance.placed = None;
+}
@@ -0,0 +1,146 @@
+use protobuf;
+
+use proto::mesos::{AgentID, CommandInfo, ContainerInfo, DiscoveryInfo,
+                   ExecutorInfo, HealthCheck, Labels, Resource, TaskID,
+                   TaskInfo};
+use util;
+
+// Builds a TaskInfo, checking at build time that the required fields are
+// present and that exactly one of command or executor is set.
+#[derive(Clone, Default)]
+pub struct TaskInfoBuilder {
+    name: Option<String>,
+    task_id: Option<TaskID>,
+    agent_id: Option<AgentID>,
+    resources: Vec<Resource>,
+    command: Option<CommandInfo>,
+    executor: Option<ExecutorInfo>,
+    container: Option<ContainerInfo>,
+    labels: Vec<(String, String)>,
+    health_check: Option<HealthCheck>,
+    discovery: Option<DiscoveryInfo>,
+    data: Option<Vec<u8>>,
+}
+
+impl TaskInfoBuilder {
+    pub fn new() -> TaskInfoBuilder {
+        TaskInfoBuilder::default()
+    }
+
+    pub fn name(mut self, name: &str) -> TaskInfoBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn task_id(mut self, task_id: &TaskID) -> TaskInfoBuilder {
+        self.task_id = Some(task_id.clone());
+        self
+    }
+
+    pub fn agent_id(mut self, agent_id: &AgentID) -> TaskInfoBuilder {
+        self.agent_id = Some(agent_id.clone());
+        self
+    }
+
+    pub fn resource(mut self, resource: Resource) -> TaskInfoBuilder {
+        self.resources.push(resource);
+        self
+    }
+
+    pub fn resources(mut self, resources: Vec<Resource>) -> TaskInfoBuilder {
+        self.resources.extend(resources);
+        self
+    }
+
+    pub fn command(mut self, command: CommandInfo) -> TaskInfoBuilder {
+        self.command = Some(command);
+        self
+    }
+
+    pub fn shell(self, value: &str) -> TaskInfoBuilder {
+        let mut command = CommandInfo::new();
+        command.set_value(value.to_string());
+        self.command(command)
+    }
+
+    pub fn executor(mut self, executor: ExecutorInfo) -> TaskInfoBuilder {
+        self.executor = Some(executor);
+        self
+    }
+
+    pub fn container(mut self, container: ContainerInfo) -> TaskInfoBuilder {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn label(mut self, key: &str, value: &str) -> TaskInfoBuilder {
+        self.labels.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn health_check(mut self,
+                        health_check: HealthCheck)
+                        -> TaskInfoBuilder {
+        self.health_check = Some(health_check);
+        self
+    }
+
+    pub fn discovery(mut self, discovery: DiscoveryInfo) -> TaskInfoBuilder {
+        self.discovery = Some(discovery);
+        self
+    }
+
+    pub fn data(mut self, data: Vec<u8>) -> TaskInfoBuilder {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn build(self) -> Result<TaskInfo, String> {
+        let name = try!(self.name.ok_or("task name is required"));
+        let task_id = try!(self.task_id.ok_or("task id is required"));
+        let agent_id = try!(self.agent_id.ok_or("agent id is required"));
+
+        let mut task_info = TaskInfo::new();
+        task_info.set_name(name);
+        task_info.set_task_id(task_id);
+        task_info.set_agent_id(agent_id);
+        task_info.set_resources(
+            protobuf::RepeatedField::from_vec(self.resources));
+
+        match (self.command, self.executor) {
+            (Some(command), None) => task_info.set_command(command),
+            (None, Some(executor)) => task_info.set_executor(executor),
+            (Some(_), Some(_)) => {
+                return Err("only one of command or executor may be set"
+                               .to_string())
+            }
+            (None, None) => {
+                return Err("one of command or executor is required"
+                               .to_string())
+            }
+        }
+
+        if let Some(container) = self.container {
+            task_info.set_container(container);
+        }
+        if self.labels.len() > 0 {
+            task_info.set_labels(labels(self.labels));
+        }
+        if let Some(health_check) = self.health_check {
+            task_info.set_health_check(health_check);
+        }
+        if let Some(discovery) = self.discovery {
+            task_info.set_discovery(discovery);
+        }
+        if let Some(data) = self.data {
+            task_info.set_data(data);
+        }
+        Ok(task_info)
+    }
+}
+
+fn labels(pairs: Vec<(String, String)>) -> Labels {
+    util::labels(pairs.iter()
+                      .map(|&(ref k, ref v)| (&**k, &**v))
+                      .collect())
+}
@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+
+use resources::Resources;
+use scheduler_client::SchedulerClient;
+use proto::mesos::{AgentID, Offer, OfferID, TaskID, TaskInfo};
+use util;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackingStrategy {
+    // Each task goes to the first agent it fits on, in offer order.
+    FirstFit,
+    // Each task goes to the agent with the least cpus, then mem, left
+    // over after placing it.
+    BestFit,
+    // Each task goes to the agent with the fewest tasks placed so far in
+    // this batch.
+    Spread,
+}
+
+// Tasks placed on one agent, to be launched with a single ACCEPT of all
+// of the agent's offers.
+#[derive(Clone, Debug)]
+pub struct Launch {
+    pub agent_id: AgentID,
+    pub offer_ids: Vec<OfferID>,
+    pub tasks: Vec<TaskInfo>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Packing {
+    pub launches: Vec<Launch>,
+    pub declined: Vec<OfferID>,
+}
+
+struct Bin {
+    agent_id: AgentID,
+    offer_ids: Vec<OfferID>,
+    remaining: Resources,
+    tasks: Vec<TaskInfo>,
+}
+
+// Holds tasks waiting for resources, and packs them into offers as they
+// arrive.  Tasks are TaskInfo templates whose agent id is filled in when
+// they are placed; their resources are what they need from an offer,
+// matched by role as written.  Tasks that fit nowhere stay queued, in
+// order, for the next batch.
+pub struct TaskQueue {
+    pub strategy: PackingStrategy,
+    // Used for declined offers and the unused part of accepted ones.
+    pub refuse_seconds: f64,
+    pending: VecDeque<TaskInfo>,
+}
+
+impl TaskQueue {
+    pub fn new(strategy: PackingStrategy) -> TaskQueue {
+        TaskQueue {
+            strategy: strategy,
+            refuse_seconds: 5f64,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn enqueue(&mut self, task: TaskInfo) {
+        self.pending.push_back(task);
+    }
+
+    pub fn remove(&mut self, task_id: &TaskID) -> Option<TaskInfo> {
+        let idx = self.pending
+                      .iter()
+                      .position(|t| {
+                          t.get_task_id().get_value() == task_id.get_value()
+                      });
+        idx.and_then(|idx| self.pending.remove(idx))
+    }
+
+    pub fn pending(&self) -> Vec<&TaskInfo> {
+        self.pending.iter().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // Places as many queued tasks as fit into the offers, removing them
+    // from the queue.  Offers from agents that got no tasks are declined.
+    pub fn pack(&mut self, offers: &[&Offer]) -> Packing {
+        let mut bins: Vec<Bin> = vec![];
+        for offer in offers {
+            let agent = offer.get_agent_id().get_value();
+            match bins.iter().position(|b| b.agent_id.get_value() == agent) {
+                Some(idx) => {
+                    let bin = &mut bins[idx];
+                    bin.offer_ids.push(offer.get_id().clone());
+                    for resource in offer.get_resources() {
+                        bin.remaining.add_resource(resource.clone());
+                    }
+                }
+                None => {
+                    bins.push(Bin {
+                        agent_id: offer.get_agent_id().clone(),
+                        offer_ids: vec![offer.get_id().clone()],
+                        remaining: Resources::from_offer(offer),
+                        tasks: vec![],
+                    })
+                }
+            }
+        }
+
+        let mut unplaced = VecDeque::new();
+        while let Some(mut task) = self.pending.pop_front() {
+            let required = Resources::from_vec(task.get_resources().to_vec());
+            match self.choose(&bins, &required) {
+                Some(idx) => {
+                    let bin = &mut bins[idx];
+                    bin.remaining = bin.remaining.clone() - required;
+                    task.set_agent_id(bin.agent_id.clone());
+                    bin.tasks.push(task);
+                }
+                None => unplaced.push_back(task),
+            }
+        }
+        self.pending = unplaced;
+
+        let mut packing = Packing {
+            launches: vec![],
+            declined: vec![],
+        };
+        for bin in bins {
+            if bin.tasks.is_empty() {
+                packing.declined.extend(bin.offer_ids);
+            } else {
+                packing.launches.push(Launch {
+                    agent_id: bin.agent_id,
+                    offer_ids: bin.offer_ids,
+                    tasks: bin.tasks,
+                });
+            }
+        }
+        packing
+    }
+
+    // Packs the offers, then sends one ACCEPT per agent and a single
+    // DECLINE for the rest.  Returns the launched tasks; tasks whose
+    // ACCEPT failed go back to the front of the queue, and its offers are
+    // declined with the rest so the master does not hold on to them.
+    pub fn offers(&mut self,
+                  client: &SchedulerClient,
+                  offers: Vec<&Offer>)
+                  -> Vec<TaskInfo> {
+        let mut packing = self.pack(&offers);
+        let mut launched = vec![];
+        let mut failed = vec![];
+        for launch in packing.launches {
+            let res = client.launch(launch.offer_ids.clone(),
+                                    launch.tasks.clone(),
+                                    Some(util::filters(self.refuse_seconds)));
+            if util::is_success(&res) {
+                launched.extend(launch.tasks);
+            } else {
+                packing.declined.extend(launch.offer_ids);
+                failed.extend(launch.tasks);
+            }
+        }
+        for task in failed.into_iter().rev() {
+            self.pending.push_front(task);
+        }
+        if packing.declined.len() > 0 {
+            client.decline(packing.declined,
+                           Some(util::filters(self.refuse_seconds)));
+        }
+        launched
+    }
+
+    fn choose(&self, bins: &[Bin], required: &Resources) -> Option<usize> {
+        let mut chosen: Option<usize> = None;
+        for (idx, bin) in bins.iter().enumerate() {
+            if !bin.remaining.contains(required) {
+                continue;
+            }
+            let better = match chosen {
+                None => true,
+                Some(best) => {
+                    match self.strategy {
+                        PackingStrategy::FirstFit => false,
+                        PackingStrategy::BestFit => {
+                            leftover(bin, required) <
+                            leftover(&bins[best], required)
+                        }
+                        PackingStrategy::Spread => {
+                            bin.tasks.len() < bins[best].tasks.len()
+                        }
+                    }
+                }
+            };
+            if better {
+                chosen = Some(idx);
+            }
+        }
+        chosen
+    }
+}
+
+fn leftover(bin: &Bin, required: &Resources) -> (f64, f64) {
+    let remaining = bin.remaining.clone() - required.clone();
+    (remaining.scalar("cpus"), remaining.scalar("mem"))
+}
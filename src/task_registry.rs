@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use proto::mesos::{AgentID, ExecutorID, TaskID, TaskInfo, TaskState,
+                   TaskStatus, TaskStatus_Reason, TaskStatus_Source};
+
+pub fn is_terminal(state: &TaskState) -> bool {
+    match *state {
+        TaskState::TASK_FINISHED |
+        TaskState::TASK_FAILED |
+        TaskState::TASK_KILLED |
+        TaskState::TASK_LOST |
+        TaskState::TASK_ERROR => true,
+        _ => false,
+    }
+}
+
+// Position of a state in the task lifecycle.  Updates may only move a
+// task forward.
+fn rank(state: &TaskState) -> u8 {
+    match *state {
+        TaskState::TASK_STAGING => 0,
+        TaskState::TASK_STARTING => 1,
+        TaskState::TASK_RUNNING => 2,
+        _ => 3,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskRecord {
+    pub task_id: TaskID,
+    pub agent_id: Option<AgentID>,
+    pub executor_id: Option<ExecutorID>,
+    pub state: TaskState,
+    pub source: Option<TaskStatus_Source>,
+    pub reason: Option<TaskStatus_Reason>,
+    pub message: Option<String>,
+    pub healthy: Option<bool>,
+}
+
+impl TaskRecord {
+    pub fn is_terminal(&self) -> bool {
+        is_terminal(&self.state)
+    }
+}
+
+// An update that would move a task backwards, usually a stale update
+// delivered after a newer one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidTransition {
+    pub task_id: String,
+    pub from: TaskState,
+    pub to: TaskState,
+}
+
+// Tracks the latest known state of every task, fed from
+// Scheduler::update.
+pub struct TaskRegistry {
+    tasks: HashMap<String, TaskRecord>,
+    on_terminal: Option<Box<FnMut(&TaskRecord)>>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> TaskRegistry {
+        TaskRegistry {
+            tasks: HashMap::new(),
+            on_terminal: None,
+        }
+    }
+
+    // Called once for each task that enters a terminal state.
+    pub fn on_terminal<F>(&mut self, callback: F)
+        where F: FnMut(&TaskRecord) + 'static
+    {
+        self.on_terminal = Some(Box::new(callback));
+    }
+
+    // Records a task we have just launched as TASK_STAGING, so it is
+    // known before its first update arrives.
+    pub fn launched(&mut self, task_info: &TaskInfo) {
+        let agent_id = if task_info.has_agent_id() {
+            Some(task_info.get_agent_id().clone())
+        } else {
+            None
+        };
+        let executor_id = if task_info.has_executor() {
+            Some(task_info.get_executor().get_executor_id().clone())
+        } else {
+            None
+        };
+        let record = TaskRecord {
+            task_id: task_info.get_task_id().clone(),
+            agent_id: agent_id,
+            executor_id: executor_id,
+            state: TaskState::TASK_STAGING,
+            source: None,
+            reason: None,
+            message: None,
+            healthy: None,
+        };
+        self.tasks.insert(task_info.get_task_id().get_value().to_string(),
+                          record);
+    }
+
+    pub fn update(&mut self,
+                  status: &TaskStatus)
+                  -> Result<&TaskRecord, InvalidTransition> {
+        let key = status.get_task_id().get_value().to_string();
+        let to = status.get_state();
+
+        let entered_terminal = match self.tasks.get(&key) {
+            Some(record) => {
+                let from = record.state.clone();
+                let backwards = rank(&to) < rank(&from);
+                let after_terminal = is_terminal(&from) && to != from;
+                if backwards || after_terminal {
+                    return Err(InvalidTransition {
+                        task_id: key,
+                        from: from,
+                        to: to,
+                    });
+                }
+                is_terminal(&to) && !is_terminal(&from)
+            }
+            None => is_terminal(&to),
+        };
+
+        {
+            let record = self.tasks.entry(key.clone()).or_insert(TaskRecord {
+                task_id: status.get_task_id().clone(),
+                agent_id: None,
+                executor_id: None,
+                state: to.clone(),
+                source: None,
+                reason: None,
+                message: None,
+                healthy: None,
+            });
+            record.state = to;
+            if status.has_agent_id() {
+                record.agent_id = Some(status.get_agent_id().clone());
+            }
+            if status.has_executor_id() {
+                record.executor_id = Some(status.get_executor_id().clone());
+            }
+            record.source = if status.has_source() {
+                Some(status.get_source())
+            } else {
+                None
+            };
+            record.reason = if status.has_reason() {
+                Some(status.get_reason())
+            } else {
+                None
+            };
+            record.message = if status.has_message() {
+                Some(status.get_message().to_string())
+            } else {
+                None
+            };
+            if status.has_healthy() {
+                record.healthy = Some(status.get_healthy());
+            }
+        }
+
+        let record = &self.tasks[&key];
+        if entered_terminal {
+            if let Some(ref mut callback) = self.on_terminal {
+                callback(record);
+            }
+        }
+        Ok(record)
+    }
+
+    pub fn get(&self, task_id: &TaskID) -> Option<&TaskRecord> {
+        self.tasks.get(task_id.get_value())
+    }
+
+    pub fn remove(&mut self, task_id: &TaskID) -> Option<TaskRecord> {
+        self.tasks.remove(task_id.get_value())
+    }
+
+    pub fn tasks(&self) -> Vec<&TaskRecord> {
+        self.tasks.values().collect()
+    }
+
+    pub fn terminal(&self) -> Vec<&TaskRecord> {
+        self.tasks.values().filter(|r| r.is_terminal()).collect()
+    }
+
+    pub fn non_terminal(&self) -> Vec<&TaskRecord> {
+        self.tasks.values().filter(|r| !r.is_terminal()).collect()
+    }
+
+    pub fn on_agent(&self, agent_id: &AgentID) -> Vec<&TaskRecord> {
+        self.tasks
+            .values()
+            .filter(|r| {
+                r.agent_id
+                 .as_ref()
+                 .map_or(false, |a| a.get_value() == agent_id.get_value())
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+}
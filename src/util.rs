@@ -1,9 +1,12 @@
+use hyper;
+use hyper::client::response::Response;
 use hyper::header::{Accept, Connection, ContentType, Headers, Quality,
                     QualityItem, qitem};
 use hyper::mime::{Mime, SubLevel, TopLevel};
 use protobuf::{self, Message};
 
 use proto::mesos::*;
+use resources;
 
 pub fn protobuf_headers() -> Headers {
     let mut headers = Headers::new();
@@ -77,6 +80,21 @@ pub fn task_info_for_container<'a>(name: &'a str,
     task_info
 }
 
+pub fn labels<'a>(pairs: Vec<(&'a str, &'a str)>) -> Labels {
+    let labels = pairs.into_iter()
+                      .map(|(key, value)| {
+                          let mut label = Label::new();
+                          label.set_key(key.to_string());
+                          label.set_value(value.to_string());
+                          label
+                      })
+                      .collect();
+
+    let mut result = Labels::new();
+    result.set_labels(protobuf::RepeatedField::from_vec(labels));
+    result
+}
+
 pub fn launch_operation(task_infos: Vec<TaskInfo>) -> Operation {
     let mut launch = Operation_Launch::new();
     launch.set_task_infos(protobuf::RepeatedField::from_vec(task_infos));
@@ -87,6 +105,46 @@ pub fn launch_operation(task_infos: Vec<TaskInfo>) -> Operation {
     operation
 }
 
+pub fn reserve_operation(resources: Vec<Resource>) -> Operation {
+    let mut reserve = Operation_Reserve::new();
+    reserve.set_resources(protobuf::RepeatedField::from_vec(resources));
+
+    let mut operation = Operation::new();
+    operation.set_field_type(Operation_Type::RESERVE);
+    operation.set_reserve(reserve);
+    operation
+}
+
+pub fn unreserve_operation(resources: Vec<Resource>) -> Operation {
+    let mut unreserve = Operation_Unreserve::new();
+    unreserve.set_resources(protobuf::RepeatedField::from_vec(resources));
+
+    let mut operation = Operation::new();
+    operation.set_field_type(Operation_Type::UNRESERVE);
+    operation.set_unreserve(unreserve);
+    operation
+}
+
+pub fn create_operation(volumes: Vec<Resource>) -> Operation {
+    let mut create = Operation_Create::new();
+    create.set_volumes(protobuf::RepeatedField::from_vec(volumes));
+
+    let mut operation = Operation::new();
+    operation.set_field_type(Operation_Type::CREATE);
+    operation.set_create(create);
+    operation
+}
+
+pub fn destroy_operation(volumes: Vec<Resource>) -> Operation {
+    let mut destroy = Operation_Destroy::new();
+    destroy.set_volumes(protobuf::RepeatedField::from_vec(volumes));
+
+    let mut operation = Operation::new();
+    operation.set_field_type(Operation_Type::DESTROY);
+    operation.set_destroy(destroy);
+    operation
+}
+
 pub fn scalar<'a>(name: &'a str, role: &'a str, value: f64) -> Resource {
     let mut scalar = Value_Scalar::new();
     scalar.set_value(value);
@@ -99,10 +157,88 @@ pub fn scalar<'a>(name: &'a str, role: &'a str, value: f64) -> Resource {
     res
 }
 
+pub fn ranges<'a>(name: &'a str,
+                  role: &'a str,
+                  ranges: Vec<(u64, u64)>)
+                  -> Resource {
+    let mut res = Resource::new();
+    res.set_name(name.to_string());
+    res.set_role(role.to_string());
+    res.set_field_type(Value_Type::RANGES);
+    res.set_ranges(resources::from_pairs(ranges));
+    res
+}
+
+pub fn set<'a>(name: &'a str, role: &'a str, items: Vec<String>) -> Resource {
+    let mut set = Value_Set::new();
+    set.set_item(protobuf::RepeatedField::from_vec(items));
+
+    let mut res = Resource::new();
+    res.set_name(name.to_string());
+    res.set_role(role.to_string());
+    res.set_field_type(Value_Type::SET);
+    res.set_set(set);
+    res
+}
+
+// Marks a resource as dynamically reserved for role by principal.
+pub fn reserved<'a>(mut resource: Resource,
+                    role: &'a str,
+                    principal: &'a str)
+                    -> Resource {
+    let mut reservation = Resource_ReservationInfo::new();
+    reservation.set_principal(principal.to_string());
+
+    resource.set_role(role.to_string());
+    resource.set_reservation(reservation);
+    resource
+}
+
+// A reserved disk resource of size megabytes, to be turned into a
+// persistent volume by a CREATE operation and mounted at container_path.
+pub fn persistent_volume<'a>(size: f64,
+                             role: &'a str,
+                             principal: &'a str,
+                             persistence_id: &'a str,
+                             container_path: &'a str,
+                             mode: Volume_Mode)
+                             -> Resource {
+    let mut persistence = Resource_DiskInfo_Persistence::new();
+    persistence.set_id(persistence_id.to_string());
+
+    let mut volume = Volume::new();
+    volume.set_container_path(container_path.to_string());
+    volume.set_mode(mode);
+
+    let mut disk = Resource_DiskInfo::new();
+    disk.set_persistence(persistence);
+    disk.set_volume(volume);
+
+    let mut res = reserved(scalar("disk", role, size), role, principal);
+    res.set_disk(disk);
+    res
+}
+
+// Filters asking the master not to offer the declined resources again
+// for refuse_seconds.
+pub fn filters(refuse_seconds: f64) -> Filters {
+    let mut filters = Filters::new();
+    filters.set_refuse_seconds(refuse_seconds);
+    filters
+}
+
+// Whether the master accepted a call.
+pub fn is_success(res: &hyper::Result<Response>) -> bool {
+    match *res {
+        Ok(ref res) => res.status.is_success(),
+        Err(_) => false,
+    }
+}
+
 pub fn get_scalar_resource_sum<'a>(name: &'a str, offers: Vec<&Offer>) -> f64 {
     offers.iter()
           .flat_map(|o| o.get_resources())
-          .filter(|r| r.get_name() == "mem")
+          .filter(|r| r.get_name() == name)
           .map(|c| c.get_scalar())
           .fold(0f64, |acc, mem_res| acc + mem_res.get_value())
 }
@@ -0,0 +1,223 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use mesos::{CallObserver, Scheduler, SchedulerClient, SchedulerConf};
+use mesos::proto::*;
+use mesos::util;
+
+pub fn agent_id(id: &str) -> AgentID {
+    let mut agent_id = AgentID::new();
+    agent_id.set_value(id.to_string());
+    agent_id
+}
+
+pub fn offer_id(id: &str) -> OfferID {
+    let mut offer_id = OfferID::new();
+    offer_id.set_value(id.to_string());
+    offer_id
+}
+
+pub fn offer(id: &str, agent: &str, resources: Vec<Resource>) -> Offer {
+    let mut offer = Offer::new();
+    offer.set_id(offer_id(id));
+    offer.set_framework_id(util::framework_id("test-framework"));
+    offer.set_agent_id(agent_id(agent));
+    offer.set_hostname(format!("{}.example.com", agent));
+    offer.set_resources(::protobuf::RepeatedField::from_vec(resources));
+    offer
+}
+
+pub fn inverse_offer(id: &str, agent: &str) -> InverseOffer {
+    let mut inverse_offer = InverseOffer::new();
+    inverse_offer.set_id(offer_id(id));
+    inverse_offer.set_framework_id(util::framework_id("test-framework"));
+    inverse_offer.set_agent_id(agent_id(agent));
+    inverse_offer
+}
+
+pub fn offers_event(offers: Vec<Offer>,
+                    inverse_offers: Vec<InverseOffer>)
+                    -> scheduler::Event {
+    let mut event_offers = scheduler::Event_Offers::new();
+    event_offers.set_offers(::protobuf::RepeatedField::from_vec(offers));
+    event_offers.set_inverse_offers(
+        ::protobuf::RepeatedField::from_vec(inverse_offers));
+
+    let mut event = scheduler::Event::new();
+    event.set_field_type(scheduler::Event_Type::OFFERS);
+    event.set_offers(event_offers);
+    event
+}
+
+pub fn conf() -> SchedulerConf {
+    SchedulerConf {
+        master_url: "http://127.0.0.1:1".to_string(),
+        user: "root".to_string(),
+        name: "rust http test".to_string(),
+        framework_timeout: 0f64,
+        implicit_acknowledgements: false,
+        offers_per_agent: true,
+        framework_id: None,
+    }
+}
+
+// Points at a port nothing listens on, so calls fail fast instead of
+// reaching a real master.
+pub fn client() -> SchedulerClient {
+    SchedulerClient::new("http://127.0.0.1:1".to_string(), None)
+}
+
+// Points at a stand-in master that answers every call with 202
+// Accepted.
+pub fn accepting_client() -> SchedulerClient {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if let Ok(stream) = stream {
+                accept_call(stream);
+            }
+        }
+    });
+    SchedulerClient::new(url, None)
+}
+
+fn accept_call(mut stream: TcpStream) {
+    let mut length = 0;
+    {
+        let mut reader = BufReader::new(&mut stream);
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim().to_lowercase();
+            if line.is_empty() {
+                break;
+            }
+            if line.starts_with("content-length:") {
+                length = line[15..].trim().parse().unwrap_or(0);
+            }
+        }
+        let mut body = vec![0; length];
+        let _ = reader.read_exact(&mut body);
+    }
+    let _ = stream.write_all(b"HTTP/1.1 202 Accepted\r\n\
+                               Content-Length: 0\r\n\
+                               Connection: close\r\n\r\n");
+}
+
+// Records the offer ids passed to each offers and inverse_offers call.
+pub struct RecordingScheduler {
+    pub offers: Vec<Vec<String>>,
+    pub inverse_offers: Vec<Vec<String>>,
+}
+
+impl RecordingScheduler {
+    pub fn new() -> RecordingScheduler {
+        RecordingScheduler {
+            offers: vec![],
+            inverse_offers: vec![],
+        }
+    }
+}
+
+impl Scheduler for RecordingScheduler {
+    fn subscribed(&mut self,
+                  _: &SchedulerClient,
+                  _: &FrameworkID,
+                  _: Option<f64>) {
+    }
+
+    fn offers(&mut self, _: &SchedulerClient, offers: Vec<&Offer>) {
+        self.offers.push(offers.iter()
+                               .map(|o| o.get_id().get_value().to_string())
+                               .collect());
+    }
+
+    fn inverse_offers(&mut self,
+                      _: &SchedulerClient,
+                      inverse_offers: Vec<&InverseOffer>) {
+        self.inverse_offers
+            .push(inverse_offers.iter()
+                                .map(|o| o.get_id().get_value().to_string())
+                                .collect());
+    }
+
+    fn rescind(&mut self, _: &SchedulerClient, _: &OfferID) {}
+
+    fn update(&mut self, _: &SchedulerClient, _: &TaskStatus) {}
+
+    fn message(&mut self,
+               _: &SchedulerClient,
+               _: &AgentID,
+               _: &ExecutorID,
+               _: Vec<u8>) {
+    }
+
+    fn failure(&mut self,
+               _: &SchedulerClient,
+               _: Option<&AgentID>,
+               _: Option<&ExecutorID>,
+               _: Option<i32>) {
+    }
+
+    fn error(&mut self, _: &SchedulerClient, _: String) {}
+
+    fn disconnected(&mut self) {}
+}
+
+pub fn status(task: &str,
+              agent: &str,
+              state: TaskState,
+              uuid: Option<&str>)
+              -> TaskStatus {
+    let mut status = TaskStatus::new();
+    status.set_task_id(util::task_id(task));
+    status.set_agent_id(agent_id(agent));
+    status.set_state(state);
+    if let Some(uuid) = uuid {
+        status.set_uuid(uuid.as_bytes().to_vec());
+    }
+    status
+}
+
+pub fn update_event(status: TaskStatus) -> scheduler::Event {
+    let mut update = scheduler::Event_Update::new();
+    update.set_status(status);
+
+    let mut event = scheduler::Event::new();
+    event.set_field_type(scheduler::Event_Type::UPDATE);
+    event.set_update(update);
+    event
+}
+
+// Records the type of every call sent through a client.
+pub struct RecordCalls {
+    pub calls: Mutex<Vec<scheduler::Call>>,
+}
+
+impl RecordCalls {
+    pub fn attach(client: &SchedulerClient) -> Arc<RecordCalls> {
+        let observer = Arc::new(RecordCalls { calls: Mutex::new(vec![]) });
+        client.add_observer(observer.clone());
+        observer
+    }
+
+    pub fn types(&self) -> Vec<scheduler::Call_Type> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c.get_field_type())
+            .collect()
+    }
+}
+
+impl CallObserver for RecordCalls {
+    fn call(&self, call: &scheduler::Call) {
+        self.calls.lock().unwrap().push(call.clone());
+    }
+}
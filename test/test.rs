@@ -1,3 +1,30 @@
 extern crate mesos;
+extern crate protobuf;
 
+mod fixtures;
+mod test_ack_tracker;
+mod test_batch_scheduler;
+mod test_command_builder;
+mod test_constraints;
+mod test_container_builder;
+mod test_decline_policy;
+mod test_discovery;
+mod test_executor_builder;
+mod test_health_check;
+mod test_kill_manager;
+mod test_maintenance;
+mod test_middleware;
+mod test_offer_inventory;
+mod test_operations;
+mod test_ports;
+mod test_reconciler;
+mod test_reservation_manager;
+mod test_resources;
+mod test_restart_policy;
+mod test_revive_manager;
+mod test_router;
 mod test_scheduler;
+mod test_service_scheduler;
+mod test_task_info_builder;
+mod test_task_queue;
+mod test_task_registry;
@@ -0,0 +1,103 @@
+use std::sync::mpsc::channel;
+
+use mesos::{AckTracker, ProtobufCallbackRouter, SchedulerRouter};
+use mesos::proto::TaskState;
+use mesos::proto::scheduler::Call_Type;
+
+use fixtures::{self, RecordCalls, RecordingScheduler};
+
+#[test]
+fn updates_without_uuid_are_not_tracked() {
+    let mut tracker = AckTracker::new();
+    let status = fixtures::status("t1", "a", TaskState::TASK_RUNNING, None);
+    assert!(!tracker.record(&status));
+    assert!(tracker.pending().is_empty());
+}
+
+#[test]
+fn acknowledgements_are_batched() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut tracker = AckTracker::new();
+    tracker.batch_size = 2;
+
+    let first = fixtures::status("t1",
+                                 "a",
+                                 TaskState::TASK_RUNNING,
+                                 Some("u1"));
+    let second = fixtures::status("t2",
+                                  "a",
+                                  TaskState::TASK_RUNNING,
+                                  Some("u2"));
+    assert!(tracker.record(&first));
+    assert!(tracker.record(&second));
+    assert_eq!(tracker.pending().len(), 2);
+
+    assert!(tracker.acknowledge(&client, &first));
+    assert!(calls.types().is_empty());
+
+    assert!(tracker.acknowledge(&client, &second));
+    assert_eq!(calls.types(),
+               vec![Call_Type::ACKNOWLEDGE, Call_Type::ACKNOWLEDGE]);
+}
+
+#[test]
+fn failed_acknowledgements_are_retried() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut tracker = AckTracker::new();
+    tracker.max_attempts = 2;
+
+    let status = fixtures::status("t1",
+                                  "a",
+                                  TaskState::TASK_RUNNING,
+                                  Some("u1"));
+    tracker.record(&status);
+
+    // nothing listens on the client's port, so every attempt fails
+    tracker.acknowledge(&client, &status);
+    assert_eq!(tracker.ready().len(), 1);
+    assert_eq!(tracker.ready()[0].attempts, 1);
+
+    assert_eq!(tracker.flush(&client), 0);
+    assert!(tracker.ready().is_empty());
+    assert_eq!(tracker.take_failed().len(), 1);
+    assert_eq!(calls.types().len(), 2);
+}
+
+#[test]
+fn router_skips_implicit_ack_without_uuid() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut conf = fixtures::conf();
+    conf.implicit_acknowledgements = true;
+
+    let (tx, rx) = channel();
+    tx.send(Ok(fixtures::update_event(fixtures::status("t1",
+                                                       "a",
+                                                       TaskState::TASK_LOST,
+                                                       None))))
+      .unwrap();
+    tx.send(Ok(fixtures::update_event(fixtures::status("t2",
+                                                       "a",
+                                                       TaskState::TASK_LOST,
+                                                       Some("u2")))))
+      .unwrap();
+    drop(tx);
+
+    let mut scheduler = RecordingScheduler::new();
+    {
+        let mut router = ProtobufCallbackRouter {
+            scheduler: &mut scheduler,
+            conf: conf.clone(),
+        };
+        router.run(rx, client, conf);
+    }
+
+    let calls = calls.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].get_acknowledge().get_task_id().get_value(), "t2");
+}
@@ -0,0 +1,128 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mesos::{BatchScheduler, PackingStrategy, Scheduler};
+use mesos::batch_scheduler::{BatchSummary, JobState};
+use mesos::proto::*;
+use mesos::proto::scheduler::Call_Type;
+use mesos::util;
+
+use fixtures;
+
+fn job() -> TaskInfo {
+    let mut command = CommandInfo::new();
+    command.set_value("./crunch".to_string());
+    let mut task = TaskInfo::new();
+    task.set_command(command);
+    task.set_resources(::protobuf::RepeatedField::from_vec(
+        vec![util::scalar("cpus", "*", 1f64)]));
+    task
+}
+
+fn cpus(id: &str) -> Offer {
+    fixtures::offer(id, "a", vec![util::scalar("cpus", "*", 4f64)])
+}
+
+fn report(scheduler: &mut BatchScheduler,
+          client: &::mesos::SchedulerClient,
+          task: &str,
+          state: TaskState,
+          message: &str) {
+    let mut status = fixtures::status(task, "a", state, None);
+    status.set_message(message.to_string());
+    scheduler.update(client, &status);
+}
+
+#[test]
+fn runs_jobs_to_completion_with_retries() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut scheduler = BatchScheduler::new(PackingStrategy::FirstFit);
+    scheduler.max_attempts = 2;
+    scheduler.teardown_on_completion = true;
+    let summaries = Rc::new(RefCell::new(vec![]));
+    let seen = summaries.clone();
+    scheduler.on_complete(move |summary, _| {
+        seen.borrow_mut().push(summary.clone());
+    });
+
+    scheduler.submit("ok", job()).unwrap();
+    scheduler.handle().submit("flaky", job());
+    assert!(scheduler.submit("ok", job()).is_err());
+
+    let offer = cpus("o1");
+    scheduler.offers(&client, vec![&offer]);
+    assert_eq!(scheduler.job("flaky").unwrap().state, JobState::Running);
+
+    report(&mut scheduler, &client, "ok.0", TaskState::TASK_FINISHED, "");
+    report(&mut scheduler, &client, "flaky.0", TaskState::TASK_FAILED, "oom");
+    assert_eq!(scheduler.job("flaky").unwrap().state, JobState::Queued);
+    assert!(!scheduler.is_complete());
+
+    let offer = cpus("o2");
+    scheduler.offers(&client, vec![&offer]);
+    report(&mut scheduler, &client, "flaky.1", TaskState::TASK_LOST, "gone");
+    assert!(scheduler.is_complete());
+
+    let flaky = scheduler.job("flaky").unwrap();
+    assert_eq!(flaky.state, JobState::Failed);
+    let history: Vec<(String, TaskState, Option<String>)> =
+        flaky.attempts
+             .iter()
+             .map(|a| {
+                 (a.task_id.get_value().to_string(),
+                  a.state.clone(),
+                  a.message.clone())
+             })
+             .collect();
+    assert_eq!(history,
+               vec![("flaky.0".to_string(),
+                     TaskState::TASK_FAILED,
+                     Some("oom".to_string())),
+                    ("flaky.1".to_string(),
+                     TaskState::TASK_LOST,
+                     Some("gone".to_string()))]);
+
+    assert_eq!(*summaries.borrow(),
+               vec![BatchSummary {
+                        succeeded: 1,
+                        failed: 1,
+                        attempts: 3,
+                    }]);
+    assert_eq!(calls.types(),
+               vec![Call_Type::ACCEPT, Call_Type::ACCEPT, Call_Type::TEARDOWN]);
+}
+
+#[test]
+fn submitting_after_completion_starts_a_new_batch() {
+    let client = fixtures::accepting_client();
+    let mut scheduler = BatchScheduler::new(PackingStrategy::FirstFit);
+
+    scheduler.submit("first", job()).unwrap();
+    let offer = cpus("o1");
+    scheduler.offers(&client, vec![&offer]);
+    report(&mut scheduler, &client, "first.0", TaskState::TASK_FINISHED, "");
+    assert!(scheduler.is_complete());
+
+    scheduler.submit("second", job()).unwrap();
+    assert!(!scheduler.is_complete());
+    assert_eq!(scheduler.summary().succeeded, 1);
+}
+
+#[test]
+fn jobs_stay_queued_when_their_launch_fails() {
+    let mut scheduler = BatchScheduler::new(PackingStrategy::FirstFit);
+    scheduler.submit("job", job()).unwrap();
+
+    let offer = cpus("o1");
+    scheduler.offers(&fixtures::client(), vec![&offer]);
+    assert_eq!(scheduler.job("job").unwrap().state, JobState::Queued);
+    assert!(scheduler.job("job").unwrap().attempts.is_empty());
+
+    let offer = cpus("o2");
+    scheduler.offers(&fixtures::accepting_client(), vec![&offer]);
+    let job = scheduler.job("job").unwrap();
+    assert_eq!(job.state, JobState::Running);
+    assert_eq!(job.attempts[0].task_id.get_value(), "job.0");
+}
@@ -0,0 +1,42 @@
+use mesos::{CommandBuilder, UriBuilder};
+
+#[test]
+fn builds_shell_command() {
+    let command = CommandBuilder::shell("./server --port $PORT0")
+                      .uri(UriBuilder::new("http://example.com/server.tgz")
+                               .extract(true)
+                               .cache(true)
+                               .build())
+                      .fetch("http://example.com/config.json")
+                      .env("LOG_LEVEL", "debug")
+                      .user("nobody")
+                      .build()
+                      .unwrap();
+
+    assert!(command.get_shell());
+    assert_eq!(command.get_value(), "./server --port $PORT0");
+    assert_eq!(command.get_uris().len(), 2);
+    assert!(command.get_uris()[0].get_extract());
+    assert!(command.get_uris()[0].get_cache());
+    assert_eq!(command.get_environment().get_variables()[0].get_name(),
+               "LOG_LEVEL");
+    assert_eq!(command.get_user(), "nobody");
+}
+
+#[test]
+fn builds_argv_command() {
+    let command = CommandBuilder::argv(vec!["/bin/echo", "hello"])
+                      .build()
+                      .unwrap();
+    assert!(!command.get_shell());
+    assert_eq!(command.get_value(), "/bin/echo");
+    assert_eq!(command.get_arguments(),
+               &["/bin/echo".to_string(), "hello".to_string()]);
+}
+
+#[test]
+fn validates_commands() {
+    assert!(CommandBuilder::shell(" ").build().is_err());
+    assert!(CommandBuilder::argv(vec![]).build().is_err());
+    assert!(CommandBuilder::shell("true").fetch("").build().is_err());
+}
@@ -0,0 +1,94 @@
+use mesos::{Constraint, PlacedTask};
+use mesos::constraints;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+fn offer_on(agent: &str, rack: &str) -> Offer {
+    let mut text = Value_Text::new();
+    text.set_value(rack.to_string());
+
+    let mut attribute = Attribute::new();
+    attribute.set_name("rack".to_string());
+    attribute.set_field_type(Value_Type::TEXT);
+    attribute.set_text(text);
+
+    let mut offer = fixtures::offer(agent, agent, vec![]);
+    offer.mut_attributes().push(attribute);
+    offer
+}
+
+fn placed(task: &str, agent: &str, rack: &str) -> PlacedTask {
+    PlacedTask::from_offer(&util::task_id(task), &offer_on(agent, rack))
+}
+
+#[test]
+fn parses_marathon_style_constraints() {
+    assert!(Constraint::parse("hostname:UNIQUE").is_ok());
+    assert!(Constraint::parse("rack:GROUP_BY").is_ok());
+    assert!(Constraint::parse("rack:GROUP_BY:3").is_ok());
+    assert!(Constraint::parse("rack:LIKE:rack-[12]").is_ok());
+    assert!(Constraint::parse("rack:MAX_PER:2").is_ok());
+
+    assert!(Constraint::parse("hostname").is_err());
+    assert!(Constraint::parse("rack:CLUSTER").is_err());
+    assert!(Constraint::parse("rack:MAX_PER:many").is_err());
+    assert!(Constraint::parse("rack:LIKE:(").is_err());
+    assert!(Constraint::parse("rack:NEAR:a").is_err());
+}
+
+#[test]
+fn unique_and_max_per_limit_tasks_per_value() {
+    let running = vec![placed("a", "agent-1", "rack-1"),
+                       placed("b", "agent-2", "rack-1")];
+    let running: Vec<&PlacedTask> = running.iter().collect();
+
+    let unique = Constraint::unique("hostname");
+    assert!(!unique.satisfied(&offer_on("agent-1", "rack-1"), &running));
+    assert!(unique.satisfied(&offer_on("agent-3", "rack-1"), &running));
+
+    let max_per = Constraint::max_per("rack", 2);
+    assert!(!max_per.satisfied(&offer_on("agent-3", "rack-1"), &running));
+    assert!(max_per.satisfied(&offer_on("agent-3", "rack-2"), &running));
+}
+
+#[test]
+fn like_unlike_and_cluster_match_attribute_values() {
+    let like = Constraint::parse("rack:LIKE:rack-[12]").unwrap();
+    let unlike = Constraint::parse("rack:UNLIKE:rack-1").unwrap();
+    let cluster = Constraint::parse("rack:CLUSTER:rack-2").unwrap();
+
+    let rack_1 = offer_on("agent-1", "rack-1");
+    let rack_2 = offer_on("agent-2", "rack-2");
+    let rack_10 = offer_on("agent-3", "rack-10");
+    let no_rack = fixtures::offer("offer-4", "agent-4", vec![]);
+
+    assert!(like.satisfied(&rack_1, &[]));
+    assert!(!like.satisfied(&rack_10, &[]));
+    assert!(!like.satisfied(&no_rack, &[]));
+
+    assert!(!unlike.satisfied(&rack_1, &[]));
+    assert!(unlike.satisfied(&rack_10, &[]));
+    assert!(unlike.satisfied(&no_rack, &[]));
+
+    assert!(cluster.satisfied(&rack_2, &[]));
+    assert!(!cluster.satisfied(&rack_1, &[]));
+    assert!(!constraints::satisfies_all(&[like, cluster], &rack_1, &[]));
+}
+
+#[test]
+fn group_by_spreads_tasks_evenly() {
+    let running = vec![placed("a", "agent-1", "rack-1"),
+                       placed("b", "agent-2", "rack-2")];
+    let running: Vec<&PlacedTask> = running.iter().collect();
+
+    // both known racks are even, so either may take the next task
+    let group_by = Constraint::group_by("rack", None);
+    assert!(group_by.satisfied(&offer_on("agent-3", "rack-1"), &running));
+
+    // with three racks expected, the empty third rack goes first
+    let group_by = Constraint::group_by("rack", Some(3));
+    assert!(!group_by.satisfied(&offer_on("agent-3", "rack-1"), &running));
+    assert!(group_by.satisfied(&offer_on("agent-3", "rack-3"), &running));
+}
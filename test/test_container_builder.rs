@@ -0,0 +1,59 @@
+use mesos::{DockerContainerBuilder, MesosContainerBuilder};
+use mesos::container_builder;
+use mesos::proto::*;
+
+#[test]
+fn builds_docker_container() {
+    let container = DockerContainerBuilder::new("nginx:1.9")
+                        .network(ContainerInfo_DockerInfo_Network::BRIDGE)
+                        .port_mapping(31000, 80, "tcp")
+                        .parameter("env", "DEBUG=1")
+                        .force_pull_image(true)
+                        .host_volume("/var/log", "/logs", Volume_Mode::RW)
+                        .build()
+                        .unwrap();
+
+    assert_eq!(container.get_field_type(), ContainerInfo_Type::DOCKER);
+    let docker = container.get_docker();
+    assert_eq!(docker.get_image(), "nginx:1.9");
+    assert_eq!(docker.get_port_mappings()[0].get_host_port(), 31000);
+    assert_eq!(docker.get_parameters()[0].get_key(), "env");
+    assert!(docker.get_force_pull_image());
+    assert_eq!(container.get_volumes()[0].get_host_path(), "/var/log");
+}
+
+#[test]
+fn docker_port_mappings_require_bridge() {
+    assert!(DockerContainerBuilder::new("nginx")
+                .network(ContainerInfo_DockerInfo_Network::HOST)
+                .port_mapping(31000, 80, "tcp")
+                .build()
+                .is_err());
+    assert!(DockerContainerBuilder::new("").build().is_err());
+}
+
+#[test]
+fn builds_mesos_container() {
+    let tools = container_builder::appc_image("example.com/tools",
+                                              None,
+                                              vec![]);
+    let container = MesosContainerBuilder::new()
+                        .appc_image("example.com/app",
+                                    Some("sha512-abc"),
+                                    vec![("version", "1.0")])
+                        .image_volume(tools, "/tools", Volume_Mode::RO)
+                        .build()
+                        .unwrap();
+
+    assert_eq!(container.get_field_type(), ContainerInfo_Type::MESOS);
+    let appc = container.get_mesos().get_image().get_appc();
+    assert_eq!(appc.get_name(), "example.com/app");
+    assert_eq!(appc.get_id(), "sha512-abc");
+    assert_eq!(appc.get_labels().get_labels()[0].get_key(), "version");
+    assert_eq!(container.get_volumes()[0].get_mode(), Volume_Mode::RO);
+
+    assert!(MesosContainerBuilder::new()
+                .host_volume("/tmp", "", Volume_Mode::RW)
+                .build()
+                .is_err());
+}
@@ -0,0 +1,64 @@
+use mesos::{DeclinePolicy, RefuseStrategy};
+use mesos::util;
+
+use fixtures;
+
+#[test]
+fn exponential_backoff_per_agent() {
+    let mut policy = DeclinePolicy::new(RefuseStrategy::Exponential {
+        initial: 5f64,
+        max: 30f64,
+    });
+    let a = fixtures::agent_id("a");
+    let b = fixtures::agent_id("b");
+
+    assert_eq!(policy.refuse_seconds(&a, true), 5f64);
+    assert_eq!(policy.refuse_seconds(&a, true), 10f64);
+    assert_eq!(policy.refuse_seconds(&a, true), 20f64);
+    assert_eq!(policy.refuse_seconds(&a, true), 30f64);
+    assert_eq!(policy.refuse_seconds(&b, true), 5f64);
+
+    policy.accepted(&a);
+    assert_eq!(policy.declines(&a), 0);
+    assert_eq!(policy.refuse_seconds(&a, true), 5f64);
+}
+
+#[test]
+fn idle_refuses_longer() {
+    let mut policy = DeclinePolicy::new(RefuseStrategy::Fixed(5f64));
+    let a = fixtures::agent_id("a");
+
+    assert_eq!(policy.filters(&a, false).get_refuse_seconds(), 300f64);
+    assert_eq!(policy.filters(&a, true).get_refuse_seconds(), 5f64);
+
+    policy.idle_refuse_seconds = None;
+    assert_eq!(policy.filters(&a, false).get_refuse_seconds(), 5f64);
+}
+
+#[test]
+fn decline_batches_by_refuse_seconds() {
+    let client = fixtures::client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut policy = DeclinePolicy::new(RefuseStrategy::Exponential {
+        initial: 5f64,
+        max: 60f64,
+    });
+    policy.refuse_seconds(&fixtures::agent_id("a"), true);
+
+    let a1 = fixtures::offer("o1", "a", vec![]);
+    let a2 = fixtures::offer("o2", "a", vec![]);
+    let b = fixtures::offer("o3", "b", vec![]);
+    policy.decline(&client, vec![&a1, &b, &a2], true);
+
+    let calls = calls.calls.lock().unwrap();
+    let declines: Vec<(f64, usize)> =
+        calls.iter()
+             .map(|c| {
+                 (c.get_decline().get_filters().get_refuse_seconds(),
+                  c.get_decline().get_offer_ids().len())
+             })
+             .collect();
+    assert_eq!(declines, vec![(10f64, 2), (5f64, 1)]);
+    assert_eq!(util::filters(7f64).get_refuse_seconds(), 7f64);
+}
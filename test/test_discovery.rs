@@ -0,0 +1,46 @@
+use mesos::{DiscoveryInfoBuilder, PortAllocator};
+use mesos::proto::*;
+
+#[test]
+fn builds_discovery_from_allocated_ports() {
+    let mut allocator = PortAllocator::new("*", vec![(31000, 31100)]);
+    let ports = allocator.allocate(2).unwrap();
+
+    let discovery = DiscoveryInfoBuilder::new("web",
+                                              DiscoveryInfo_Visibility::CLUSTER)
+                        .environment("prod")
+                        .version("1.2.0")
+                        .allocated_ports(&ports,
+                                         &[("http", "tcp"), ("admin", "tcp")])
+                        .label("team", "edge")
+                        .build()
+                        .unwrap();
+
+    assert_eq!(discovery.get_name(), "web");
+    assert_eq!(discovery.get_visibility(), DiscoveryInfo_Visibility::CLUSTER);
+    assert_eq!(discovery.get_environment(), "prod");
+    let advertised = discovery.get_ports().get_ports();
+    assert_eq!(advertised[0].get_number(), 31000);
+    assert_eq!(advertised[0].get_name(), "http");
+    assert_eq!(advertised[1].get_number(), 31001);
+    assert_eq!(advertised[1].get_name(), "admin");
+    assert_eq!(discovery.get_labels().get_labels()[0].get_key(), "team");
+}
+
+#[test]
+fn rejects_inconsistent_ports() {
+    assert!(DiscoveryInfoBuilder::new("web",
+                                      DiscoveryInfo_Visibility::FRAMEWORK)
+                .allocated_ports(&[31000, 31001], &[("http", "tcp")])
+                .build()
+                .is_err());
+    assert!(DiscoveryInfoBuilder::new("web",
+                                      DiscoveryInfo_Visibility::FRAMEWORK)
+                .port(31000, "http", "tcp")
+                .port(31001, "http", "tcp")
+                .build()
+                .is_err());
+    assert!(DiscoveryInfoBuilder::new("", DiscoveryInfo_Visibility::EXTERNAL)
+                .build()
+                .is_err());
+}
@@ -0,0 +1,73 @@
+use mesos::{CommandBuilder, ExecutorInfoBuilder, TaskInfoBuilder};
+use mesos::executor_builder;
+use mesos::util;
+
+use fixtures::{self, RecordCalls};
+
+#[test]
+fn builds_executor_info() {
+    let command = CommandBuilder::shell("./executor").build().unwrap();
+    let executor = ExecutorInfoBuilder::new("worker-executor")
+                       .name("worker")
+                       .command(command)
+                       .resource(util::scalar("cpus", "*", 0.1f64))
+                       .source("workers")
+                       .build()
+                       .unwrap();
+    assert_eq!(executor.get_executor_id().get_value(), "worker-executor");
+    assert_eq!(executor.get_source(), "workers");
+    assert_eq!(executor.get_resources().len(), 1);
+
+    assert!(ExecutorInfoBuilder::new("no-command").build().is_err());
+    assert!(ExecutorInfoBuilder::new("")
+                .command(CommandBuilder::shell("true").build().unwrap())
+                .build()
+                .is_err());
+}
+
+#[test]
+fn launches_tasks_on_shared_executor() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let executor = ExecutorInfoBuilder::new("worker-executor")
+                       .command(CommandBuilder::shell("./executor")
+                                    .build()
+                                    .unwrap())
+                       .build()
+                       .unwrap();
+
+    let mut offer = fixtures::offer("o1", "a", vec![]);
+    assert!(!executor_builder::is_running(&[&offer],
+                                          executor.get_executor_id()));
+    offer.mut_executor_ids().push(executor.get_executor_id().clone());
+    assert!(executor_builder::is_running(&[&offer],
+                                         executor.get_executor_id()));
+
+    let tasks = (0..2)
+                    .map(|i| {
+                        TaskInfoBuilder::new()
+                            .name("work")
+                            .task_id(&util::task_id(&format!("work-{}", i)))
+                            .agent_id(&fixtures::agent_id("a"))
+                            .shell("unused")
+                            .build()
+                            .unwrap()
+                    })
+                    .collect();
+    client.launch_with_executor(vec![offer.get_id().clone()],
+                                executor,
+                                tasks,
+                                None);
+
+    let calls = calls.calls.lock().unwrap();
+    let launched = calls[0].get_accept().get_operations()[0]
+                       .get_launch()
+                       .get_task_infos();
+    assert_eq!(launched.len(), 2);
+    for task in launched {
+        assert!(!task.has_command());
+        assert_eq!(task.get_executor().get_executor_id().get_value(),
+                   "worker-executor");
+    }
+}
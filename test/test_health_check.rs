@@ -0,0 +1,77 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use mesos::{CommandBuilder, HealthCheckBuilder, HealthPolicy, HealthTracker};
+use mesos::proto::*;
+use mesos::proto::scheduler::Call_Type;
+
+use fixtures::{self, RecordCalls};
+
+fn health(task: &str, healthy: bool) -> TaskStatus {
+    let mut status = fixtures::status(task, "a", TaskState::TASK_RUNNING, None);
+    status.set_healthy(healthy);
+    status
+}
+
+#[test]
+fn builds_health_checks() {
+    let http = HealthCheckBuilder::http(8080, "/health")
+                   .statuses(vec![200, 204])
+                   .interval_seconds(5f64)
+                   .grace_period_seconds(30f64)
+                   .consecutive_failures(3)
+                   .build()
+                   .unwrap();
+    assert_eq!(http.get_http().get_path(), "/health");
+    assert_eq!(http.get_http().get_statuses(), &[200, 204]);
+    assert_eq!(http.get_consecutive_failures(), 3);
+
+    let command = CommandBuilder::shell("curl -f localhost").build().unwrap();
+    assert!(HealthCheckBuilder::command(command).build().is_ok());
+
+    assert!(HealthCheckBuilder::http(0, "/").build().is_err());
+    assert!(HealthCheckBuilder::http(80, "/")
+                .timeout_seconds(-1f64)
+                .build()
+                .is_err());
+}
+
+#[test]
+fn kills_tasks_that_stay_unhealthy() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut tracker = HealthTracker::new(HealthPolicy::KillAndRelaunch);
+    tracker.max_unhealthy = 2;
+
+    assert!(!tracker.update(&client, &health("t1", false)));
+    assert!(!tracker.update(&client, &health("t1", true)));
+    assert!(!tracker.update(&client, &health("t1", false)));
+    assert!(tracker.update(&client, &health("t1", false)));
+    assert!(!tracker.update(&client, &health("t1", false)));
+
+    assert_eq!(calls.types(), vec![Call_Type::KILL]);
+    assert_eq!(tracker.unhealthy().len(), 1);
+
+    let killed = fixtures::status("t1", "a", TaskState::TASK_KILLED, None);
+    tracker.update(&client, &killed);
+    assert!(tracker.unhealthy().is_empty());
+}
+
+#[test]
+fn notifies_without_killing() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let notified = Rc::new(Cell::new(0));
+    let mut tracker = HealthTracker::new(HealthPolicy::Notify);
+    tracker.max_unhealthy = 1;
+    {
+        let notified = notified.clone();
+        tracker.on_unhealthy(move |_| notified.set(notified.get() + 1));
+    }
+
+    assert!(tracker.update(&client, &health("t1", false)));
+    assert_eq!(notified.get(), 1);
+    assert!(calls.types().is_empty());
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use mesos::KillManager;
+use mesos::executor_builder;
+use mesos::proto::*;
+use mesos::proto::scheduler::Call_Type;
+use mesos::util;
+
+use fixtures;
+
+#[test]
+fn retries_until_terminal_update() {
+    let client = fixtures::client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut manager = KillManager::new();
+    manager.initial_backoff = Duration::from_secs(0);
+    let task_id = util::task_id("t1");
+    manager.kill(&client, &task_id, Some(&fixtures::agent_id("a")), None);
+    manager.tick(&client);
+    assert_eq!(manager.pending()[0].attempts, 2);
+
+    assert!(!manager.update(&fixtures::status("t1",
+                                              "a",
+                                              TaskState::TASK_RUNNING,
+                                              None)));
+    assert!(manager.update(&fixtures::status("t1",
+                                             "a",
+                                             TaskState::TASK_KILLED,
+                                             None)));
+    manager.tick(&client);
+    assert!(!manager.is_pending(&task_id));
+    assert_eq!(calls.types(), vec![Call_Type::KILL, Call_Type::KILL]);
+}
+
+#[test]
+fn escalates_to_shutdown_then_gives_up() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let failing = fixtures::client();
+
+    let mut manager = KillManager::new();
+    manager.shutdown_after = Duration::from_secs(0);
+    manager.kill(&client,
+                 &util::task_id("t1"),
+                 Some(&fixtures::agent_id("a")),
+                 Some(&executor_builder::executor_id("e1")));
+
+    // a SHUTDOWN lost on the way is sent again
+    manager.tick(&failing);
+    assert!(!manager.pending()[0].shutdown);
+    manager.tick(&client);
+    manager.tick(&client);
+    assert_eq!(calls.types(), vec![Call_Type::KILL, Call_Type::SHUTDOWN]);
+    assert!(manager.pending()[0].shutdown);
+    assert!(manager.take_failed().is_empty());
+
+    manager.give_up_after = Duration::from_secs(0);
+    manager.tick(&client);
+    let failed = manager.take_failed();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].task_id.get_value(), "t1");
+    assert!(manager.pending().is_empty());
+}
@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mesos::{MaintenanceManager, TaskRegistry};
+use mesos::offer_inventory;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+const HOUR: i64 = 3_600_000_000_000;
+
+fn unavailability(start: i64) -> Unavailability {
+    let mut time = TimeInfo::new();
+    time.set_nanoseconds(start);
+    let mut duration = DurationInfo::new();
+    duration.set_nanoseconds(HOUR);
+
+    let mut unavailability = Unavailability::new();
+    unavailability.set_start(time);
+    unavailability.set_duration(duration);
+    unavailability
+}
+
+fn inverse_offer(id: &str, agent: &str, start: i64) -> InverseOffer {
+    let mut inverse_offer = fixtures::inverse_offer(id, agent);
+    inverse_offer.set_unavailability(unavailability(start));
+    inverse_offer
+}
+
+#[test]
+fn answers_inverse_offers_with_reasons() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let now = offer_inventory::now_nanos();
+
+    let mut manager = MaintenanceManager::new();
+    let i1 = inverse_offer("i1", "a", now + HOUR);
+    let i2 = inverse_offer("i2", "b", now + HOUR);
+    manager.inverse_offers(vec![&i1, &i2]);
+    assert_eq!(manager.pending_inverse_offers().len(), 2);
+
+    // an answer the master never got leaves the inverse offer pending
+    assert!(!manager.accept(&fixtures::client(),
+                            &fixtures::offer_id("i1"),
+                            "drained"));
+    assert_eq!(manager.pending_inverse_offers().len(), 2);
+    assert!(manager.responses().is_empty());
+
+    assert!(manager.accept(&client, &fixtures::offer_id("i1"), "drained"));
+    assert!(manager.decline(&client, &fixtures::offer_id("i2"), "busy"));
+    assert!(!manager.accept(&client, &fixtures::offer_id("i1"), "again"));
+
+    assert_eq!(calls.types(),
+               vec![scheduler::Call_Type::ACCEPT,
+                    scheduler::Call_Type::DECLINE]);
+    let responses = manager.responses();
+    assert_eq!(responses.len(), 2);
+    assert!(responses[0].accepted);
+    assert_eq!(responses[1].reason, "busy");
+    assert_eq!(responses[1].agent_id.get_value(), "b");
+}
+
+#[test]
+fn avoids_and_drains_agents_entering_maintenance() {
+    let now = offer_inventory::now_nanos();
+    let mut manager = MaintenanceManager::new();
+
+    let mut soon = fixtures::offer("o1", "a", vec![]);
+    soon.set_unavailability(unavailability(now + HOUR / 2));
+    let mut later = fixtures::offer("o2", "b", vec![]);
+    later.set_unavailability(unavailability(now + 5 * HOUR));
+    let plain = fixtures::offer("o3", "c", vec![]);
+    manager.offers(vec![&soon, &later, &plain]);
+
+    let usable: Vec<&str> = manager.usable(vec![&soon, &later, &plain])
+                                   .iter()
+                                   .map(|o| o.get_id().get_value())
+                                   .collect();
+    assert_eq!(usable, vec!["o2", "o3"]);
+
+    let mut registry = TaskRegistry::new();
+    let mut command = CommandInfo::new();
+    command.set_value("sleep 10".to_string());
+    registry.launched(&util::task_info("t1",
+                                       &util::task_id("t1"),
+                                       &fixtures::agent_id("a"),
+                                       &command,
+                                       vec![]));
+
+    let drained = Rc::new(RefCell::new(vec![]));
+    let seen = drained.clone();
+    manager.on_drain(move |agent, _, tasks| {
+        seen.borrow_mut().push((agent.get_value().to_string(), tasks.len()));
+    });
+    assert_eq!(manager.drain(&registry).len(), 1);
+    assert!(manager.drain(&registry).is_empty());
+    assert_eq!(*drained.borrow(), vec![("a".to_string(), 1)]);
+
+    // maintenance was cancelled
+    let cancelled = fixtures::offer("o4", "a", vec![]);
+    manager.offers(vec![&cancelled]);
+    assert!(!manager.is_draining(&fixtures::agent_id("a")));
+}
@@ -0,0 +1,114 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::channel;
+
+use mesos::{MiddlewareRouter, ProtobufCallbackRouter,
+            SchedulerClient, SchedulerMiddleware, SchedulerRouter};
+use mesos::proto::scheduler::{Call_Type, Event, Event_Type};
+use mesos::scheduler_middleware::{AgentBlacklist, CallLogger, EventLogger};
+
+use fixtures::{self, RecordCalls, RecordingScheduler};
+
+struct SwallowOffers;
+
+impl SchedulerMiddleware for SwallowOffers {
+    fn event(&mut self, _: &SchedulerClient, event: Event) -> Option<Event> {
+        if event.get_field_type() == Event_Type::OFFERS {
+            None
+        } else {
+            Some(event)
+        }
+    }
+}
+
+// A Write that can still be read after being boxed into a logger.
+#[derive(Clone)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn route(middleware: Vec<Box<SchedulerMiddleware>>,
+         client: SchedulerClient)
+         -> RecordingScheduler {
+    let mut scheduler = RecordingScheduler::new();
+    let conf = fixtures::conf();
+
+    let (tx, rx) = channel();
+    tx.send(Ok(fixtures::offers_event(vec![fixtures::offer("o1", "a", vec![]),
+                                           fixtures::offer("o2", "b", vec![])],
+                                      vec![fixtures::inverse_offer("i1",
+                                                                   "b")])))
+      .unwrap();
+    drop(tx);
+
+    {
+        let mut inner = ProtobufCallbackRouter {
+            scheduler: &mut scheduler,
+            conf: conf.clone(),
+        };
+        let mut router = MiddlewareRouter::new(&mut inner);
+        for m in middleware {
+            router.add(m);
+        }
+        router.run(rx, client, conf);
+    }
+    scheduler
+}
+
+#[test]
+fn middleware_can_swallow_events() {
+    let scheduler = route(vec![Box::new(SwallowOffers)], fixtures::client());
+    assert!(scheduler.offers.is_empty());
+    assert!(scheduler.inverse_offers.is_empty());
+}
+
+#[test]
+fn blacklisted_agents_are_declined() {
+    let client = fixtures::client();
+    let observer = RecordCalls::attach(&client);
+
+    let blacklist = AgentBlacklist::new(vec!["b".to_string()]);
+    let scheduler = route(vec![Box::new(blacklist)], client);
+
+    assert_eq!(scheduler.offers, vec![vec!["o1".to_string()]]);
+    assert!(scheduler.inverse_offers.is_empty());
+    // inverse offers are left unanswered rather than declined
+    let calls = observer.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].get_field_type(), Call_Type::DECLINE);
+    let declined: Vec<&str> = calls[0].get_decline()
+                                      .get_offer_ids()
+                                      .iter()
+                                      .map(|o| o.get_value())
+                                      .collect();
+    assert_eq!(declined, vec!["o2"]);
+}
+
+#[test]
+fn loggers_write_to_the_given_sink() {
+    let client = fixtures::client();
+    let events = SharedBuffer(Arc::new(Mutex::new(vec![])));
+    let calls = SharedBuffer(Arc::new(Mutex::new(vec![])));
+    client.add_observer(Arc::new(CallLogger::new(Box::new(calls.clone()))));
+
+    route(vec![Box::new(EventLogger::new(Box::new(events.clone()))),
+               Box::new(AgentBlacklist::new(vec!["b".to_string()]))],
+          client);
+
+    assert_eq!(events.contents(), "received event OFFERS\n");
+    assert_eq!(calls.contents(), "sending call DECLINE\n");
+}
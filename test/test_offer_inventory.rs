@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use mesos::OfferInventory;
+use mesos::offer_inventory;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+fn unavailability(start: i64, duration: Option<i64>) -> Unavailability {
+    let mut time = TimeInfo::new();
+    time.set_nanoseconds(start);
+
+    let mut unavailability = Unavailability::new();
+    unavailability.set_start(time);
+    if let Some(nanos) = duration {
+        let mut duration = DurationInfo::new();
+        duration.set_nanoseconds(nanos);
+        unavailability.set_duration(duration);
+    }
+    unavailability
+}
+
+#[test]
+fn offers_cannot_be_used_twice() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut inventory = OfferInventory::new();
+    let o1 = fixtures::offer("o1", "a", vec![]);
+    let o2 = fixtures::offer("o2", "a", vec![]);
+    inventory.add(vec![&o1, &o2, &o1]);
+    assert_eq!(inventory.len(), 2);
+    assert_eq!(inventory.on_agent(&fixtures::agent_id("a")).len(), 2);
+
+    let ids = vec![fixtures::offer_id("o1")];
+    let res = inventory.launch(&client, ids.clone(), vec![], None).unwrap();
+    assert!(util::is_success(&res));
+    assert!(inventory.launch(&client, ids, vec![], None).is_none());
+
+    // all or nothing, o2 stays held
+    let ids = vec![fixtures::offer_id("o1"), fixtures::offer_id("o2")];
+    assert!(inventory.take(&ids).is_none());
+    assert_eq!(inventory.len(), 1);
+
+    assert!(inventory.rescind(&fixtures::offer_id("o2")).is_some());
+    assert!(inventory.is_empty());
+    assert_eq!(calls.types(), vec![scheduler::Call_Type::ACCEPT]);
+
+    // a failed call is reported, not mistaken for a used offer
+    let o3 = fixtures::offer("o3", "a", vec![]);
+    inventory.add(vec![&o3]);
+    let res = inventory.accept(&fixtures::client(),
+                               vec![fixtures::offer_id("o3")],
+                               vec![],
+                               None);
+    assert!(res.unwrap().is_err());
+}
+
+#[test]
+fn tick_declines_expired_offers() {
+    let client = fixtures::client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut inventory = OfferInventory::new();
+    let o1 = fixtures::offer("o1", "a", vec![]);
+    inventory.add(vec![&o1]);
+    assert!(inventory.tick(&client).is_empty());
+
+    inventory.max_hold = Duration::from_secs(0);
+    inventory.refuse_seconds = 10f64;
+    assert_eq!(inventory.tick(&client).len(), 1);
+    assert!(inventory.is_empty());
+
+    let calls = calls.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].get_decline().get_filters().get_refuse_seconds(),
+               10f64);
+}
+
+#[test]
+fn available_skips_agents_going_away() {
+    let now = offer_inventory::now_nanos();
+    let hour = 3_600_000_000_000;
+
+    let mut draining = fixtures::offer("o1", "a", vec![]);
+    draining.set_unavailability(unavailability(now - hour, Some(2 * hour)));
+    let mut later = fixtures::offer("o2", "b", vec![]);
+    later.set_unavailability(unavailability(now + hour, None));
+    let mut past = fixtures::offer("o3", "c", vec![]);
+    past.set_unavailability(unavailability(now - 2 * hour, Some(hour)));
+
+    let mut inventory = OfferInventory::new();
+    inventory.add(vec![&draining, &later, &past]);
+    let ids = |offers: Vec<&Offer>| -> Vec<String> {
+        offers.iter().map(|o| o.get_id().get_value().to_string()).collect()
+    };
+    assert_eq!(ids(inventory.available()), vec!["o2", "o3"]);
+
+    inventory.unavailability_horizon = Duration::from_secs(2 * 3600);
+    assert_eq!(ids(inventory.available()), vec!["o3"]);
+}
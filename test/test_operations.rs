@@ -0,0 +1,67 @@
+use mesos::Resources;
+use mesos::proto::*;
+use mesos::proto::scheduler::Call_Type;
+use mesos::util;
+
+use fixtures::{self, RecordCalls};
+
+#[test]
+fn persistent_volumes_are_reserved_disk() {
+    let volume = util::persistent_volume(1024f64,
+                                         "db",
+                                         "ops",
+                                         "db-0",
+                                         "data",
+                                         Volume_Mode::RW);
+    assert_eq!(volume.get_name(), "disk");
+    assert_eq!(volume.get_role(), "db");
+    assert_eq!(volume.get_reservation().get_principal(), "ops");
+    assert_eq!(volume.get_disk().get_persistence().get_id(), "db-0");
+    assert_eq!(volume.get_disk().get_volume().get_mode(), Volume_Mode::RW);
+
+    let resources = Resources::from_vec(vec![volume.clone(), volume]);
+    assert_eq!(resources.persistent_volumes().as_slice().len(), 2);
+}
+
+#[test]
+fn reserve_create_and_launch_in_one_accept() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let cpus = util::reserved(util::scalar("cpus", "*", 1f64), "db", "ops");
+    let volume = util::persistent_volume(1024f64,
+                                         "db",
+                                         "ops",
+                                         "db-0",
+                                         "data",
+                                         Volume_Mode::RW);
+    let disk = util::reserved(util::scalar("disk", "*", 1024f64),
+                              "db",
+                              "ops");
+
+    let mut command = CommandInfo::new();
+    command.set_value("postgres".to_string());
+    let task = util::task_info("db-0",
+                               &util::task_id("db-0"),
+                               &fixtures::agent_id("a"),
+                               &command,
+                               vec![cpus.clone(), volume.clone()]);
+
+    client.reserve_and_launch(vec![fixtures::offer_id("o1")],
+                              vec![cpus, disk],
+                              vec![volume],
+                              vec![task],
+                              None);
+
+    let calls = calls.calls.lock().unwrap();
+    assert_eq!(calls[0].get_field_type(), Call_Type::ACCEPT);
+    let types: Vec<Operation_Type> = calls[0].get_accept()
+                                             .get_operations()
+                                             .iter()
+                                             .map(|o| o.get_field_type())
+                                             .collect();
+    assert_eq!(types,
+               vec![Operation_Type::RESERVE,
+                    Operation_Type::CREATE,
+                    Operation_Type::LAUNCH]);
+}
@@ -0,0 +1,34 @@
+use mesos::{PortAllocator, Resources};
+use mesos::util;
+
+use fixtures;
+
+#[test]
+fn allocates_from_offered_ranges() {
+    let offer = fixtures::offer("o1",
+                                "a",
+                                vec![util::ranges("ports",
+                                                  "*",
+                                                  vec![(31000, 31001),
+                                                       (31005, 31010)])]);
+    let mut allocator = PortAllocator::from_offer(&offer, "*");
+    assert_eq!(allocator.count(), 8);
+
+    let first = allocator.allocate(3).unwrap();
+    assert_eq!(first, vec![31000, 31001, 31005]);
+
+    assert!(allocator.allocate_specific(&[31005]).is_none());
+    let second = allocator.allocate_specific(&[31010, 31007]).unwrap();
+    assert_eq!(second, vec![31010, 31007]);
+
+    assert!(allocator.allocate(4).is_none());
+    assert_eq!(allocator.available(), &[(31006, 31006), (31008, 31009)]);
+
+    // what we hand out plus what remains adds up to the offer
+    let used = Resources::from_vec(vec![allocator.resource(&first),
+                                        allocator.resource(&second)]);
+    let total = used.clone() +
+                Resources::from_vec(vec![allocator.remaining()]);
+    assert_eq!(total.ranges("ports"), vec![(31000, 31001), (31005, 31010)]);
+    assert!(Resources::from_offer(&offer).contains(&used));
+}
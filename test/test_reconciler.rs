@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use mesos::Reconciler;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures::{self, RecordCalls};
+
+fn reconciled(calls: &RecordCalls) -> Vec<usize> {
+    calls.calls
+         .lock()
+         .unwrap()
+         .iter()
+         .map(|c| c.get_reconcile().get_tasks().len())
+         .collect()
+}
+
+#[test]
+fn reconciles_until_every_task_is_confirmed() {
+    let client = fixtures::accepting_client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut reconciler = Reconciler::new();
+    reconciler.initial_backoff = Duration::from_millis(0);
+    reconciler.subscribed(&client,
+                          vec![(util::task_id("t1"),
+                                Some(fixtures::agent_id("a"))),
+                               (util::task_id("t2"), None)]);
+    assert_eq!(reconciled(&calls), vec![2]);
+
+    let mut status = fixtures::status("t1", "a", TaskState::TASK_RUNNING, None);
+    status.set_reason(TaskStatus_Reason::REASON_RECONCILIATION);
+    assert!(!reconciler.update(&client, &status));
+    assert!(!reconciler.is_converged());
+
+    // only the unconfirmed task is retried
+    reconciler.tick(&client);
+    assert_eq!(reconciled(&calls), vec![2, 1]);
+
+    let status = fixtures::status("t2", "b", TaskState::TASK_LOST, None);
+    assert!(reconciler.update(&client, &status));
+    assert!(reconciler.is_converged());
+
+    // finish with an implicit reconciliation
+    assert_eq!(reconciled(&calls), vec![2, 1, 0]);
+}
+
+#[test]
+fn implicit_reconciliation_without_known_tasks() {
+    let client = fixtures::accepting_client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut reconciler = Reconciler::new();
+    reconciler.subscribed(&client, vec![]);
+    assert!(reconciler.is_converged());
+    assert_eq!(reconciled(&calls), vec![0]);
+}
+
+#[test]
+fn failed_implicit_reconciliation_is_retried() {
+    let client = fixtures::client();
+    let calls = RecordCalls::attach(&client);
+
+    let mut reconciler = Reconciler::new();
+    reconciler.initial_backoff = Duration::from_millis(0);
+    reconciler.subscribed(&client, vec![]);
+    assert!(!reconciler.is_converged());
+
+    reconciler.tick(&client);
+    assert_eq!(reconciled(&calls), vec![0, 0]);
+}
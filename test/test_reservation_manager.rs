@@ -0,0 +1,188 @@
+use mesos::ReservationManager;
+use mesos::proto::*;
+use mesos::reservation_manager::{InstanceSpec, InstanceState};
+use mesos::util;
+
+use fixtures;
+
+fn spec() -> InstanceSpec {
+    InstanceSpec {
+        resources: vec![util::scalar("cpus", "*", 1f64),
+                        util::scalar("mem", "*", 256f64)],
+        volume_size: 1024f64,
+        container_path: "data".to_string(),
+    }
+}
+
+fn operation_types(operations: &[Operation]) -> Vec<Operation_Type> {
+    operations.iter().map(|o| o.get_field_type()).collect()
+}
+
+#[test]
+fn reserves_then_relaunches_on_same_volume() {
+    let mut manager = ReservationManager::new("db", "ops");
+    manager.add_instance("db-0", spec());
+
+    let unreserved = fixtures::offer("o1",
+                                     "a",
+                                     vec![util::scalar("cpus", "*", 4f64),
+                                          util::scalar("mem", "*", 1024f64),
+                                          util::scalar("disk",
+                                                       "*",
+                                                       4096f64)]);
+    let placements = manager.plan(&unreserved);
+    assert_eq!(placements.len(), 1);
+    assert_eq!(operation_types(&placements[0].operations),
+               vec![Operation_Type::RESERVE, Operation_Type::CREATE]);
+    assert_eq!(placements[0].resources.len(), 3);
+    assert_eq!(manager.get("db-0").unwrap().state, InstanceState::Running);
+
+    // the task dies, and the agent offers back the reservation
+    manager.stopped("db-0");
+    let volume = util::persistent_volume(1024f64,
+                                         "db",
+                                         "ops",
+                                         "db-0",
+                                         "data",
+                                         Volume_Mode::RW);
+    let reserved = vec![util::reserved(util::scalar("cpus", "*", 1f64),
+                                       "db",
+                                       "ops"),
+                        util::reserved(util::scalar("mem", "*", 256f64),
+                                       "db",
+                                       "ops"),
+                        volume.clone()];
+
+    // other agents are ignored
+    let elsewhere = fixtures::offer("o2", "b", reserved.clone());
+    assert!(manager.plan(&elsewhere).is_empty());
+
+    let back = fixtures::offer("o3", "a", reserved);
+    let placements = manager.plan(&back);
+    assert_eq!(placements.len(), 1);
+    assert!(placements[0].operations.is_empty());
+    assert!(placements[0].resources.contains(&volume));
+    assert_eq!(manager.get("db-0").unwrap().state, InstanceState::Running);
+}
+
+#[test]
+fn decommissioned_instances_release_their_resources() {
+    let mut manager = ReservationManager::new("db", "ops");
+    manager.restore("db-0", spec(), "db-0", None);
+    manager.decommission("db-0");
+
+    let volume = util::persistent_volume(1024f64,
+                                         "db",
+                                         "ops",
+                                         "db-0",
+                                         "data",
+                                         Volume_Mode::RW);
+    let offer = fixtures::offer("o1",
+                                "a",
+                                vec![util::reserved(util::scalar("cpus",
+                                                                 "*",
+                                                                 1f64),
+                                                    "db",
+                                                    "ops"),
+                                     util::reserved(util::scalar("mem",
+                                                                 "*",
+                                                                 256f64),
+                                                    "db",
+                                                    "ops"),
+                                     volume]);
+    let placements = manager.plan(&offer);
+    assert_eq!(placements.len(), 1);
+    assert_eq!(operation_types(&placements[0].operations),
+               vec![Operation_Type::DESTROY, Operation_Type::UNRESERVE]);
+    assert_eq!(placements[0].operations[1]
+                   .get_unreserve()
+                   .get_resources()
+                   .len(),
+               3);
+    assert!(manager.get("db-0").is_none());
+}
+
+#[test]
+fn reservations_outlive_the_volume_until_offered() {
+    let mut manager = ReservationManager::new("db", "ops");
+    manager.restore("db-0", spec(), "db-0", Some(fixtures::agent_id("a")));
+    manager.decommission("db-0");
+
+    let volume = util::persistent_volume(1024f64,
+                                         "db",
+                                         "ops",
+                                         "db-0",
+                                         "data",
+                                         Volume_Mode::RW);
+    let placements = manager.plan(&fixtures::offer("o1", "a", vec![volume]));
+    assert_eq!(operation_types(&placements[0].operations),
+               vec![Operation_Type::DESTROY, Operation_Type::UNRESERVE]);
+    assert_eq!(manager.get("db-0").unwrap().state,
+               InstanceState::Unreserving);
+
+    let reserved = vec![util::reserved(util::scalar("cpus", "*", 1f64),
+                                       "db",
+                                       "ops"),
+                        util::reserved(util::scalar("mem", "*", 256f64),
+                                       "db",
+                                       "ops")];
+    let placements = manager.plan(&fixtures::offer("o2", "a", reserved));
+    assert_eq!(placements.len(), 1);
+    assert_eq!(operation_types(&placements[0].operations),
+               vec![Operation_Type::UNRESERVE]);
+    assert_eq!(placements[0].operations[0]
+                   .get_unreserve()
+                   .get_resources()
+                   .len(),
+               2);
+    assert!(manager.get("db-0").is_none());
+}
+
+#[test]
+fn failed_placements_are_rolled_back() {
+    let mut manager = ReservationManager::new("db", "ops");
+    manager.add_instance("db-0", spec());
+    manager.restore("db-1", spec(), "db-1", None);
+
+    let volume = util::persistent_volume(1024f64,
+                                         "db",
+                                         "ops",
+                                         "db-1",
+                                         "data",
+                                         Volume_Mode::RW);
+    let offer = fixtures::offer("o1",
+                                "a",
+                                vec![util::scalar("cpus", "*", 4f64),
+                                     util::scalar("mem", "*", 1024f64),
+                                     util::scalar("disk", "*", 4096f64),
+                                     util::reserved(util::scalar("cpus",
+                                                                 "*",
+                                                                 1f64),
+                                                    "db",
+                                                    "ops"),
+                                     util::reserved(util::scalar("mem",
+                                                                 "*",
+                                                                 256f64),
+                                                    "db",
+                                                    "ops"),
+                                     volume]);
+    assert_eq!(manager.plan(&offer).len(), 2);
+
+    // the ACCEPT failed, so nothing was reserved or launched
+    manager.launch_failed("db-0");
+    manager.launch_failed("db-1");
+    let fresh = manager.get("db-0").unwrap();
+    assert_eq!(fresh.state, InstanceState::Unplaced);
+    assert!(fresh.agent_id.is_none());
+    let relaunch = manager.get("db-1").unwrap();
+    assert_eq!(relaunch.state, InstanceState::Stopped);
+    assert_eq!(relaunch.agent_id.as_ref().unwrap().get_value(), "a");
+
+    // instances bound to the agent come first
+    let placements = manager.plan(&offer);
+    assert_eq!(placements.len(), 2);
+    assert_eq!(placements[0].instance, "db-1");
+    assert!(placements[0].operations.is_empty());
+    assert_eq!(operation_types(&placements[1].operations),
+               vec![Operation_Type::RESERVE, Operation_Type::CREATE]);
+}
@@ -0,0 +1,101 @@
+use mesos::Resources;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+#[test]
+fn scalar_arithmetic() {
+    let offered = Resources::from_vec(vec![util::scalar("cpus", "*", 2f64),
+                                           util::scalar("cpus", "*", 2f64),
+                                           util::scalar("mem", "*", 512f64)]);
+    assert_eq!(offered.scalar("cpus"), 4f64);
+
+    let task = Resources::from_vec(vec![util::scalar("cpus", "*", 1.5f64),
+                                        util::scalar("mem", "*", 512f64)]);
+    assert!(offered.contains(&task));
+
+    let remaining = offered.clone() - task.clone();
+    assert_eq!(remaining.scalar("cpus"), 2.5f64);
+    assert_eq!(remaining.scalar("mem"), 0f64);
+    assert!(!remaining.contains(&task));
+    assert_eq!(remaining + task, offered);
+}
+
+#[test]
+fn ranges_and_sets() {
+    let offered = Resources::from_vec(vec![util::ranges("ports",
+                                                        "*",
+                                                        vec![(31000, 31005)]),
+                                           util::ranges("ports",
+                                                        "*",
+                                                        vec![(31006, 31010)]),
+                                           util::set("disks",
+                                                     "*",
+                                                     vec!["sda".to_string(),
+                                                          "sdb".to_string()])]);
+    assert_eq!(offered.ranges("ports"), vec![(31000, 31010)]);
+
+    let used = Resources::from_vec(vec![util::ranges("ports",
+                                                     "*",
+                                                     vec![(31002, 31003)]),
+                                        util::set("disks",
+                                                  "*",
+                                                  vec!["sda".to_string()])]);
+    assert!(offered.contains(&used));
+
+    let remaining = offered - used.clone();
+    assert_eq!(remaining.ranges("ports"),
+               vec![(31000, 31001), (31004, 31010)]);
+    assert_eq!(remaining.set("disks"), vec!["sdb".to_string()]);
+    assert!(!remaining.contains(&used));
+}
+
+#[test]
+fn single_resources_are_normalized() {
+    let offered = Resources::from_vec(vec![util::ranges("ports",
+                                                        "*",
+                                                        vec![(1, 5), (6, 10)]),
+                                           util::set("disks",
+                                                     "*",
+                                                     vec!["sda".to_string(),
+                                                          "sda".to_string()])]);
+    let spanning = Resources::from_vec(vec![util::ranges("ports",
+                                                         "*",
+                                                         vec![(4, 7)])]);
+    assert!(offered.contains(&spanning));
+    assert_eq!(offered.set("disks"), vec!["sda".to_string()]);
+    assert_eq!(offered.as_slice()[1].get_set().get_item().len(), 1);
+}
+
+#[test]
+fn roles_are_kept_apart() {
+    let mut reserved = util::scalar("cpus", "db", 2f64);
+    let mut reservation = Resource_ReservationInfo::new();
+    reservation.set_principal("ops".to_string());
+    reserved.set_reservation(reservation);
+
+    let offer = fixtures::offer("o1",
+                                "a",
+                                vec![util::scalar("cpus", "*", 1f64),
+                                     reserved]);
+    let offered = Resources::from_offer(&offer);
+
+    assert_eq!(offered.scalar("cpus"), 3f64);
+    assert_eq!(offered.reserved().scalar("cpus"), 2f64);
+    assert_eq!(offered.reserved_by("ops").scalar("cpus"), 2f64);
+    assert_eq!(offered.unreserved().scalar("cpus"), 1f64);
+
+    let task = Resources::from_vec(vec![util::scalar("cpus", "*", 2f64)]);
+    assert!(!offered.contains(&task));
+    assert!(offered.flatten("*").contains(&task));
+}
+
+#[test]
+fn scalar_resource_sum_respects_name() {
+    let offer = fixtures::offer("o1",
+                                "a",
+                                vec![util::scalar("cpus", "*", 4f64),
+                                     util::scalar("mem", "*", 128f64)]);
+    assert_eq!(util::get_scalar_resource_sum("cpus", vec![&offer]), 4f64);
+}
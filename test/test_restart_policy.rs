@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use mesos::{RestartManager, RestartPolicy};
+use mesos::proto::*;
+use mesos::restart_policy::{InstanceState, Restart};
+use mesos::util;
+
+use fixtures;
+
+fn terminated(task: &str, state: TaskState) -> TaskStatus {
+    fixtures::status(task, "a", state, None)
+}
+
+#[test]
+fn policies_decide_on_state_and_reason() {
+    let failed = terminated("t", TaskState::TASK_FAILED);
+    let finished = terminated("t", TaskState::TASK_FINISHED);
+    let mut invalid = terminated("t", TaskState::TASK_ERROR);
+    invalid.set_reason(TaskStatus_Reason::REASON_TASK_INVALID);
+
+    let on_failure = RestartPolicy::OnFailure { max_retries: 2 };
+    assert!(on_failure.should_restart(&failed, 1));
+    assert!(!on_failure.should_restart(&failed, 2));
+    assert!(!on_failure.should_restart(&finished, 0));
+    assert!(!on_failure.should_restart(&invalid, 0));
+
+    assert!(RestartPolicy::Always.should_restart(&finished, 10));
+    assert!(!RestartPolicy::Never.should_restart(&failed, 0));
+}
+
+#[test]
+fn restarts_instances_with_backoff() {
+    let mut manager = RestartManager::new();
+    manager.initial_backoff = Duration::from_secs(0);
+    manager.generation = 7;
+    manager.add("web-0", RestartPolicy::OnFailure { max_retries: 1 });
+    assert_eq!(manager.ready().len(), 1);
+
+    let task_id = manager.get("web-0").unwrap().next_task_id();
+    assert_eq!(task_id.get_value(), "web-0.7.0");
+    manager.launched("web-0", &task_id);
+    assert!(manager.ready().is_empty());
+
+    // updates for unknown tasks are ignored
+    assert_eq!(manager.update(&terminated("other", TaskState::TASK_LOST)),
+               None);
+    assert_eq!(manager.update(&terminated("web-0.7.0", TaskState::TASK_LOST)),
+               Some(Restart::After(Duration::from_secs(0))));
+    assert_eq!(manager.ready()[0].next_task_id().get_value(), "web-0.7.1");
+
+    manager.launched("web-0", &util::task_id("web-0.7.1"));
+    assert_eq!(manager.update(&terminated("web-0.7.1",
+                                          TaskState::TASK_FAILED)),
+               Some(Restart::Done));
+    assert_eq!(manager.get("web-0").unwrap().state, InstanceState::Done);
+}
+
+#[test]
+fn stopped_and_custom_decisions() {
+    let mut manager = RestartManager::new();
+    manager.initial_backoff = Duration::from_secs(0);
+    manager.add("a", RestartPolicy::Always);
+    manager.add("b", RestartPolicy::Never);
+    manager.launched("a", &util::task_id("a.0"));
+    manager.launched("b", &util::task_id("b.0"));
+
+    manager.stop("a");
+    assert_eq!(manager.update(&terminated("a.0", TaskState::TASK_KILLED)),
+               Some(Restart::Done));
+
+    manager.decide_with(|_, status| {
+        status.get_reason() == TaskStatus_Reason::REASON_AGENT_REMOVED
+    });
+    let mut lost = terminated("b.0", TaskState::TASK_LOST);
+    lost.set_reason(TaskStatus_Reason::REASON_AGENT_REMOVED);
+    assert_eq!(manager.update(&lost),
+               Some(Restart::After(Duration::from_secs(0))));
+}
+
+#[test]
+fn task_ids_differ_between_managers() {
+    let mut first = RestartManager::new();
+    first.generation = 1;
+    first.add("db-0", RestartPolicy::Always);
+    // e.g. a scheduler restarted under the same framework id
+    let mut second = RestartManager::new();
+    second.generation = 2;
+    second.add("db-0", RestartPolicy::Always);
+
+    assert!(first.get("db-0").unwrap().next_task_id() !=
+            second.get("db-0").unwrap().next_task_id());
+}
@@ -0,0 +1,67 @@
+use mesos::ReviveManager;
+use mesos::proto::scheduler::Call_Type;
+
+use fixtures;
+
+#[test]
+fn suppresses_when_idle_and_revives_on_work() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut manager = ReviveManager::new();
+    manager.subscribed(&client);
+    assert!(manager.is_suppressed());
+
+    assert!(manager.set_pending(&client, 2));
+    assert!(!manager.set_pending(&client, 1));
+    assert!(manager.set_pending(&client, 0));
+    assert!(!manager.set_pending(&client, 0));
+    assert!(manager.is_suppressed());
+
+    assert_eq!(calls.types(),
+               vec![Call_Type::SUPPRESS,
+                    Call_Type::REVIVE,
+                    Call_Type::SUPPRESS]);
+}
+
+#[test]
+fn reapplies_state_after_resubscribing() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut manager = ReviveManager::new();
+    manager.subscribed(&client);
+    manager.disconnected();
+
+    // nothing is sent while disconnected
+    assert!(!manager.set_pending(&client, 3));
+    manager.subscribed(&client);
+    assert!(!manager.is_suppressed());
+
+    manager.disconnected();
+    manager.set_pending(&client, 0);
+    manager.subscribed(&client);
+    assert!(manager.is_suppressed());
+
+    assert_eq!(calls.types(),
+               vec![Call_Type::SUPPRESS, Call_Type::SUPPRESS]);
+}
+
+#[test]
+fn failed_calls_are_sent_again() {
+    let client = fixtures::accepting_client();
+    let failing = fixtures::client();
+    let calls = fixtures::RecordCalls::attach(&failing);
+
+    let mut manager = ReviveManager::new();
+    manager.subscribed(&client);
+
+    // the master still suppresses the framework after a failed REVIVE
+    assert!(manager.set_pending(&failing, 1));
+    assert!(manager.is_suppressed());
+    assert!(manager.set_pending(&failing, 2));
+
+    assert!(manager.set_pending(&client, 3));
+    assert!(!manager.is_suppressed());
+    assert_eq!(calls.types(), vec![Call_Type::REVIVE, Call_Type::REVIVE]);
+}
@@ -0,0 +1,57 @@
+use std::sync::mpsc::channel;
+
+use mesos::{ProtobufCallbackRouter, SchedulerRouter};
+
+use fixtures::{self, RecordingScheduler};
+
+fn route(offers_per_agent: bool) -> RecordingScheduler {
+    let mut scheduler = RecordingScheduler::new();
+    let mut conf = fixtures::conf();
+    conf.offers_per_agent = offers_per_agent;
+
+    let event = fixtures::offers_event(vec![fixtures::offer("o1", "a", vec![]),
+                                            fixtures::offer("o2", "b", vec![]),
+                                            fixtures::offer("o3", "a", vec![])],
+                                       vec![fixtures::inverse_offer("i1",
+                                                                    "b"),
+                                            fixtures::inverse_offer("i2",
+                                                                    "a"),
+                                            fixtures::inverse_offer("i3",
+                                                                    "b")]);
+    let (tx, rx) = channel();
+    tx.send(Ok(event)).unwrap();
+    drop(tx);
+
+    {
+        let mut router = ProtobufCallbackRouter {
+            scheduler: &mut scheduler,
+            conf: conf.clone(),
+        };
+        router.run(rx, fixtures::client(), conf);
+    }
+    scheduler
+}
+
+#[test]
+fn offers_are_grouped_per_agent() {
+    let scheduler = route(true);
+    assert_eq!(scheduler.offers,
+               vec![vec!["o1".to_string(), "o3".to_string()],
+                    vec!["o2".to_string()]]);
+    assert_eq!(scheduler.inverse_offers,
+               vec![vec!["i1".to_string(), "i3".to_string()],
+                    vec!["i2".to_string()]]);
+}
+
+#[test]
+fn offers_are_delivered_as_one_batch() {
+    let scheduler = route(false);
+    assert_eq!(scheduler.offers,
+               vec![vec!["o1".to_string(),
+                         "o2".to_string(),
+                         "o3".to_string()]]);
+    assert_eq!(scheduler.inverse_offers,
+               vec![vec!["i1".to_string(),
+                         "i2".to_string(),
+                         "i3".to_string()]]);
+}
@@ -141,6 +141,7 @@ fn main() {
         name: "rust http".to_string(),
         framework_timeout: 0f64,
         implicit_acknowledgements: true,
+        offers_per_agent: true,
         framework_id: None,
     };
 
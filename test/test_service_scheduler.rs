@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use mesos::{AppDefinition, Constraint, Scheduler, ServiceScheduler};
+use mesos::proto::*;
+use mesos::proto::scheduler::Call_Type;
+use mesos::service_scheduler::InstanceState;
+use mesos::util;
+
+use fixtures;
+
+fn app(instances: usize) -> AppDefinition {
+    let mut command = CommandInfo::new();
+    command.set_value("./serve".to_string());
+    let mut app = AppDefinition::new("web",
+                                     command,
+                                     vec![util::scalar("cpus", "*", 1f64),
+                                          util::scalar("mem", "*", 128f64)],
+                                     instances);
+    app.constraints = vec![Constraint::unique("hostname")];
+    app
+}
+
+fn agent_offer(id: &str, agent: &str) -> Offer {
+    fixtures::offer(id,
+                    agent,
+                    vec![util::scalar("cpus", "*", 4f64),
+                         util::scalar("mem", "*", 1024f64)])
+}
+
+fn task_ids(scheduler: &ServiceScheduler) -> Vec<String> {
+    scheduler.instances()
+             .iter()
+             .filter_map(|i| i.task_id.as_ref())
+             .map(|t| t.get_value().to_string())
+             .collect()
+}
+
+fn report(scheduler: &mut ServiceScheduler,
+          client: &::mesos::SchedulerClient,
+          state: TaskState) {
+    for task_id in task_ids(scheduler) {
+        scheduler.update(client, &fixtures::status(&task_id, "a", state, None));
+    }
+}
+
+#[test]
+fn launches_instances_within_constraints() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let mut scheduler = ServiceScheduler::new(app(3)).unwrap();
+
+    let a1 = agent_offer("o1", "a");
+    let b1 = agent_offer("o2", "b");
+    scheduler.offers(&client, vec![&a1, &b1]);
+    assert_eq!(calls.types(), vec![Call_Type::ACCEPT, Call_Type::ACCEPT]);
+
+    // the third instance cannot share a host with the others
+    let a2 = agent_offer("o3", "a");
+    let c1 = agent_offer("o4", "c");
+    scheduler.offers(&client, vec![&a2, &c1]);
+    let states: Vec<InstanceState> = scheduler.instances()
+                                              .iter()
+                                              .map(|i| i.state.clone())
+                                              .collect();
+    assert_eq!(states, vec![InstanceState::Staging; 3]);
+    let calls = calls.calls.lock().unwrap();
+    assert_eq!(calls[2].get_accept().get_offer_ids()[0].get_value(), "o4");
+    assert_eq!(calls[3].get_field_type(), Call_Type::DECLINE);
+    assert_eq!(calls[3].get_decline().get_offer_ids()[0].get_value(), "o3");
+}
+
+#[test]
+fn relaunches_failures_and_scales() {
+    let client = fixtures::accepting_client();
+    let mut scheduler = ServiceScheduler::new(app(2)).unwrap();
+    scheduler.initial_backoff = Duration::from_secs(0);
+    let handle = scheduler.handle();
+
+    let a = agent_offer("o1", "a");
+    let b = agent_offer("o2", "b");
+    scheduler.offers(&client, vec![&a, &b]);
+    let failed = scheduler.instances()[0].task_id.clone().unwrap();
+    scheduler.update(&client,
+                     &fixtures::status(failed.get_value(),
+                                       "a",
+                                       TaskState::TASK_FAILED,
+                                       None));
+    assert_eq!(scheduler.instances()[0].state, InstanceState::Pending);
+    assert_eq!(scheduler.instances()[0].failures, 1);
+
+    let a = agent_offer("o3", "a");
+    scheduler.offers(&client, vec![&a]);
+    assert_eq!(scheduler.instances()[0].state, InstanceState::Staging);
+    assert!(scheduler.instances()[0].task_id.as_ref() != Some(&failed));
+
+    // scaling down kills the extra instance, and forgets it once dead
+    handle.scale(1);
+    scheduler.heartbeat(&client);
+    assert_eq!(scheduler.instances()[1].state, InstanceState::Killing);
+    report(&mut scheduler, &client, TaskState::TASK_KILLED);
+    assert_eq!(scheduler.instances().len(), 1);
+}
+
+#[test]
+fn rolls_instances_onto_new_definition() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let mut scheduler = ServiceScheduler::new(app(2)).unwrap();
+    let handle = scheduler.handle();
+
+    let a = agent_offer("o1", "a");
+    let b = agent_offer("o2", "b");
+    scheduler.offers(&client, vec![&a, &b]);
+    report(&mut scheduler, &client, TaskState::TASK_RUNNING);
+
+    let mut updated = app(2);
+    updated.command.set_value("./serve --v2".to_string());
+    handle.update(updated).unwrap();
+    scheduler.heartbeat(&client);
+    assert_eq!(scheduler.version(), 1);
+
+    // only one instance is taken down at a time
+    let killing: Vec<String> = scheduler.instances()
+                                        .iter()
+                                        .filter(|i| {
+                                            i.state == InstanceState::Killing
+                                        })
+                                        .map(|i| i.name.clone())
+                                        .collect();
+    assert_eq!(killing, vec!["web-0".to_string()]);
+    assert_eq!(calls.types().last(), Some(&Call_Type::KILL));
+
+    let killed = scheduler.instances()[0].task_id.clone().unwrap();
+    scheduler.update(&client,
+                     &fixtures::status(killed.get_value(),
+                                       "a",
+                                       TaskState::TASK_KILLED,
+                                       None));
+    let a = agent_offer("o3", "a");
+    scheduler.offers(&client, vec![&a]);
+    assert_eq!(scheduler.instances()[0].version, 1);
+    assert_eq!(scheduler.instances()[1].state, InstanceState::Running);
+
+    let relaunched = scheduler.instances()[0].task_id.clone().unwrap();
+    scheduler.update(&client,
+                     &fixtures::status(relaunched.get_value(),
+                                       "a",
+                                       TaskState::TASK_RUNNING,
+                                       None));
+    assert_eq!(scheduler.instances()[1].state, InstanceState::Killing);
+}
+
+#[test]
+fn failed_launches_wait_for_the_next_offer() {
+    let client = fixtures::client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let mut scheduler = ServiceScheduler::new(app(1)).unwrap();
+
+    let a = agent_offer("o1", "a");
+    scheduler.offers(&client, vec![&a]);
+    assert_eq!(calls.types(), vec![Call_Type::ACCEPT, Call_Type::DECLINE]);
+    assert_eq!(scheduler.decline_policy.declines(&fixtures::agent_id("a")),
+               0);
+    let instance = scheduler.instances()[0];
+    assert_eq!(instance.state, InstanceState::Pending);
+    assert!(instance.task_id.is_none());
+    assert!(scheduler.registry().non_terminal().is_empty());
+
+    let a = agent_offer("o2", "a");
+    scheduler.offers(&fixtures::accepting_client(), vec![&a]);
+    assert_eq!(scheduler.instances()[0].state, InstanceState::Staging);
+}
+
+#[test]
+fn stuck_kills_shut_down_the_executor_then_give_up() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let mut scheduler = ServiceScheduler::new(app(2)).unwrap();
+    let handle = scheduler.handle();
+
+    let a = agent_offer("o1", "a");
+    let b = agent_offer("o2", "b");
+    scheduler.offers(&client, vec![&a, &b]);
+    for task_id in task_ids(&scheduler) {
+        let mut status = fixtures::status(&task_id,
+                                          "a",
+                                          TaskState::TASK_RUNNING,
+                                          None);
+        let mut executor_id = ExecutorID::new();
+        executor_id.set_value("e".to_string());
+        status.set_executor_id(executor_id);
+        scheduler.update(&client, &status);
+    }
+
+    handle.scale(1);
+    scheduler.kills.shutdown_after = Duration::from_secs(0);
+    scheduler.heartbeat(&client);
+    assert_eq!(scheduler.instances()[1].state, InstanceState::Killing);
+    assert!(calls.types().contains(&Call_Type::SHUTDOWN));
+
+    // the scaled down instance is forgotten once the kill is abandoned
+    scheduler.kills.give_up_after = Duration::from_secs(0);
+    scheduler.heartbeat(&client);
+    assert_eq!(scheduler.instances().len(), 1);
+    assert!(scheduler.kills.take_failed().is_empty());
+}
+
+#[test]
+fn running_updates_during_a_kill_do_not_advance_the_roll() {
+    let client = fixtures::accepting_client();
+    let mut scheduler = ServiceScheduler::new(app(2)).unwrap();
+    scheduler.initial_backoff = Duration::from_secs(60);
+    let handle = scheduler.handle();
+
+    let a = agent_offer("o1", "a");
+    let b = agent_offer("o2", "b");
+    scheduler.offers(&client, vec![&a, &b]);
+    report(&mut scheduler, &client, TaskState::TASK_RUNNING);
+
+    let mut updated = app(2);
+    updated.command.set_value("./serve --v2".to_string());
+    handle.update(updated).unwrap();
+    scheduler.heartbeat(&client);
+    assert_eq!(scheduler.instances()[0].state, InstanceState::Killing);
+
+    // e.g. a reconciliation answer for the task being killed
+    let killing = scheduler.instances()[0].task_id.clone().unwrap();
+    let mut status = fixtures::status(killing.get_value(),
+                                      "a",
+                                      TaskState::TASK_RUNNING,
+                                      None);
+    status.set_reason(TaskStatus_Reason::REASON_RECONCILIATION);
+    scheduler.update(&client, &status);
+    assert_eq!(scheduler.instances()[0].state, InstanceState::Killing);
+    assert_eq!(scheduler.instances()[1].state, InstanceState::Running);
+
+    scheduler.update(&client,
+                     &fixtures::status(killing.get_value(),
+                                       "a",
+                                       TaskState::TASK_KILLED,
+                                       None));
+    assert_eq!(scheduler.instances()[0].state, InstanceState::Pending);
+    assert_eq!(scheduler.instances()[0].failures, 0);
+}
@@ -0,0 +1,54 @@
+use mesos::TaskInfoBuilder;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+#[test]
+fn builds_command_task() {
+    let task = TaskInfoBuilder::new()
+                   .name("web")
+                   .task_id(&util::task_id("web-1"))
+                   .agent_id(&fixtures::agent_id("a"))
+                   .shell("python -m SimpleHTTPServer")
+                   .resource(util::scalar("cpus", "*", 0.5f64))
+                   .resource(util::scalar("mem", "*", 64f64))
+                   .label("app", "web")
+                   .data(vec![1, 2, 3])
+                   .build()
+                   .unwrap();
+
+    assert_eq!(task.get_name(), "web");
+    assert_eq!(task.get_command().get_value(), "python -m SimpleHTTPServer");
+    assert_eq!(task.get_resources().len(), 2);
+    assert_eq!(task.get_labels().get_labels()[0].get_value(), "web");
+    assert_eq!(task.get_data(), &[1, 2, 3]);
+    assert!(!task.has_executor());
+}
+
+#[test]
+fn requires_exactly_one_of_command_or_executor() {
+    let base = TaskInfoBuilder::new()
+                   .name("web")
+                   .task_id(&util::task_id("web-1"))
+                   .agent_id(&fixtures::agent_id("a"));
+
+    assert!(base.clone().build().is_err());
+    assert!(base.clone()
+                .shell("true")
+                .executor(ExecutorInfo::new())
+                .build()
+                .is_err());
+    assert!(base.executor(ExecutorInfo::new()).build().is_ok());
+}
+
+#[test]
+fn requires_identity_fields() {
+    let err = TaskInfoBuilder::new()
+                  .task_id(&util::task_id("web-1"))
+                  .agent_id(&fixtures::agent_id("a"))
+                  .shell("true")
+                  .build()
+                  .unwrap_err();
+    assert_eq!(err, "task name is required");
+}
@@ -0,0 +1,138 @@
+use mesos::{PackingStrategy, TaskQueue};
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+fn task(id: &str, cpus: f64, mem: f64) -> TaskInfo {
+    let mut command = CommandInfo::new();
+    command.set_value("sleep 10".to_string());
+    util::task_info(id,
+                    &util::task_id(id),
+                    &fixtures::agent_id("unplaced"),
+                    &command,
+                    vec![util::scalar("cpus", "*", cpus),
+                         util::scalar("mem", "*", mem)])
+}
+
+fn agent_offer(id: &str, agent: &str, cpus: f64, mem: f64) -> Offer {
+    fixtures::offer(id,
+                    agent,
+                    vec![util::scalar("cpus", "*", cpus),
+                         util::scalar("mem", "*", mem)])
+}
+
+fn placements(queue: &mut TaskQueue, offers: &[&Offer]) -> Vec<Vec<String>> {
+    queue.pack(offers)
+         .launches
+         .iter()
+         .map(|l| {
+             l.tasks
+              .iter()
+              .map(|t| {
+                  format!("{}@{}",
+                          t.get_task_id().get_value(),
+                          t.get_agent_id().get_value())
+              })
+              .collect()
+         })
+         .collect()
+}
+
+#[test]
+fn first_fit_fills_agents_in_order() {
+    let mut queue = TaskQueue::new(PackingStrategy::FirstFit);
+    for id in &["t1", "t2", "t3"] {
+        queue.enqueue(task(id, 1f64, 128f64));
+    }
+    let a = agent_offer("o1", "a", 2f64, 1024f64);
+    let b = agent_offer("o2", "b", 4f64, 1024f64);
+
+    assert_eq!(placements(&mut queue, &[&a, &b]),
+               vec![vec!["t1@a".to_string(), "t2@a".to_string()],
+                    vec!["t3@b".to_string()]]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn best_fit_prefers_tightest_agent() {
+    let mut queue = TaskQueue::new(PackingStrategy::BestFit);
+    queue.enqueue(task("t1", 1f64, 128f64));
+    let a = agent_offer("o1", "a", 4f64, 1024f64);
+    let b = agent_offer("o2", "b", 1f64, 512f64);
+
+    assert_eq!(placements(&mut queue, &[&a, &b]),
+               vec![vec!["t1@b".to_string()]]);
+}
+
+#[test]
+fn spread_round_robins_and_keeps_what_does_not_fit() {
+    let mut queue = TaskQueue::new(PackingStrategy::Spread);
+    for id in &["t1", "t2", "t3"] {
+        queue.enqueue(task(id, 1f64, 128f64));
+    }
+    queue.enqueue(task("big", 8f64, 128f64));
+    let a = agent_offer("o1", "a", 4f64, 1024f64);
+    let b = agent_offer("o2", "b", 4f64, 1024f64);
+
+    assert_eq!(placements(&mut queue, &[&a, &b]),
+               vec![vec!["t1@a".to_string(), "t3@a".to_string()],
+                    vec!["t2@b".to_string()]]);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.pending()[0].get_task_id().get_value(), "big");
+}
+
+#[test]
+fn offers_accepts_per_agent_and_declines_the_rest() {
+    let client = fixtures::accepting_client();
+    let calls = fixtures::RecordCalls::attach(&client);
+
+    let mut queue = TaskQueue::new(PackingStrategy::FirstFit);
+    queue.refuse_seconds = 30f64;
+    queue.enqueue(task("t1", 1f64, 128f64));
+    let a1 = agent_offer("o1", "a", 0.5f64, 1024f64);
+    let a2 = agent_offer("o2", "a", 0.5f64, 0f64);
+    let b = agent_offer("o3", "b", 4f64, 1024f64);
+
+    let launched = queue.offers(&client, vec![&a1, &b, &a2]);
+    assert_eq!(launched.len(), 1);
+    assert_eq!(launched[0].get_agent_id().get_value(), "a");
+
+    let calls = calls.calls.lock().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].get_accept().get_offer_ids().len(), 2);
+    assert_eq!(calls[1].get_field_type(), scheduler::Call_Type::DECLINE);
+    assert_eq!(calls[1].get_decline().get_offer_ids()[0].get_value(), "o3");
+    assert_eq!(calls[1].get_decline().get_filters().get_refuse_seconds(),
+               30f64);
+}
+
+#[test]
+fn failed_launches_are_requeued_in_order() {
+    let client = fixtures::client();
+    let calls = fixtures::RecordCalls::attach(&client);
+    let mut queue = TaskQueue::new(PackingStrategy::FirstFit);
+    for id in &["t1", "t2", "t3"] {
+        queue.enqueue(task(id, 1f64, 128f64));
+    }
+    let a = agent_offer("o1", "a", 1f64, 1024f64);
+    let b = agent_offer("o2", "b", 1f64, 1024f64);
+
+    assert!(queue.offers(&client, vec![&a, &b]).is_empty());
+    let pending: Vec<&str> = queue.pending()
+                                  .iter()
+                                  .map(|t| t.get_task_id().get_value())
+                                  .collect();
+    assert_eq!(pending, vec!["t1", "t2", "t3"]);
+
+    // the offers of the failed ACCEPTs are handed back
+    let calls = calls.calls.lock().unwrap();
+    let last = calls.last().unwrap();
+    assert_eq!(last.get_field_type(), scheduler::Call_Type::DECLINE);
+    let declined: Vec<&str> = last.get_decline()
+                                  .get_offer_ids()
+                                  .iter()
+                                  .map(|o| o.get_value())
+                                  .collect();
+    assert_eq!(declined, vec!["o1", "o2"]);
+}
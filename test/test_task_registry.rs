@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mesos::TaskRegistry;
+use mesos::proto::*;
+use mesos::util;
+
+use fixtures;
+
+#[test]
+fn stale_updates_are_rejected() {
+    let mut registry = TaskRegistry::new();
+    let running = fixtures::status("t1", "a", TaskState::TASK_RUNNING, None);
+    let finished = fixtures::status("t1", "a", TaskState::TASK_FINISHED, None);
+    let staging = fixtures::status("t1", "a", TaskState::TASK_STAGING, None);
+
+    assert!(registry.update(&running).is_ok());
+    assert!(registry.update(&staging).is_err());
+    assert!(registry.update(&finished).is_ok());
+
+    let err = registry.update(&running).unwrap_err();
+    assert_eq!(err.from, TaskState::TASK_FINISHED);
+    assert_eq!(err.to, TaskState::TASK_RUNNING);
+
+    let record = registry.get(&util::task_id("t1")).unwrap();
+    assert_eq!(record.state, TaskState::TASK_FINISHED);
+    assert_eq!(record.agent_id.as_ref().unwrap().get_value(), "a");
+}
+
+#[test]
+fn terminal_callback_fires_once() {
+    let terminal = Rc::new(RefCell::new(vec![]));
+    let mut registry = TaskRegistry::new();
+    {
+        let terminal = terminal.clone();
+        registry.on_terminal(move |r| {
+            terminal.borrow_mut().push(r.task_id.get_value().to_string())
+        });
+    }
+
+    let mut lost = fixtures::status("t1", "a", TaskState::TASK_LOST, None);
+    lost.set_reason(TaskStatus_Reason::REASON_AGENT_REMOVED);
+
+    registry.update(&fixtures::status("t1", "a", TaskState::TASK_RUNNING, None))
+            .unwrap();
+    registry.update(&lost).unwrap();
+    registry.update(&lost).unwrap();
+    registry.update(&fixtures::status("t2", "a", TaskState::TASK_RUNNING, None))
+            .unwrap();
+
+    assert_eq!(*terminal.borrow(), vec!["t1".to_string()]);
+    assert_eq!(registry.terminal().len(), 1);
+    assert_eq!(registry.non_terminal().len(), 1);
+    assert_eq!(registry.get(&util::task_id("t1")).unwrap().reason,
+               Some(TaskStatus_Reason::REASON_AGENT_REMOVED));
+}